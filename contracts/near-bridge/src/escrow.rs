@@ -0,0 +1,47 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, AccountId};
+
+/// Token id used for native NEAR locked with `lock_near`
+///
+/// Not a valid account id, so it cannot collide with a NEP-141 contract.
+pub const NATIVE_TOKEN_ID: &str = "near:native";
+
+/// NEP-297 standard name for bridge events
+pub(crate) const EVENT_STANDARD: &str = "aurora-bridge";
+pub(crate) const EVENT_VERSION: &str = "1.0.0";
+
+/// A NEAR-side asset escrowed for transfer to Stellar
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LockRecord {
+    pub nonce: u64,
+    /// NEP-141 contract account, or `NATIVE_TOKEN_ID`
+    pub token: String,
+    pub amount: u128,
+    pub sender: AccountId,
+    pub recipient_chain: u8,
+    /// Recipient address on the destination chain
+    pub recipient: String,
+    /// Block timestamp (seconds)
+    pub timestamp: u64,
+}
+
+/// `msg` accepted by `ft_on_transfer`
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LockMessage {
+    pub recipient_chain: u8,
+    pub recipient: String,
+}
+
+/// Log a NEP-297 `lock` event for the relayer
+pub fn emit_lock_event(record: &LockRecord) {
+    let event = near_sdk::serde_json::json!({
+        "standard": EVENT_STANDARD,
+        "version": EVENT_VERSION,
+        "event": "lock",
+        "data": [record],
+    });
+    env::log_str(&format!("EVENT_JSON:{}", event));
+}
@@ -0,0 +1,84 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{ext_contract, AccountId, Gas, NearToken};
+
+/// Storage key of the wrapped token wasm uploaded with `set_token_code`
+pub const TOKEN_CODE_KEY: &[u8] = b"c";
+
+/// Suffix of wrapped token sub-accounts (`usdc-stellar.bridge.near`)
+pub const TOKEN_ACCOUNT_SUFFIX: &str = "-stellar";
+
+/// Deposit funding a new token account's storage (3 NEAR)
+pub const TOKEN_DEPLOY_DEPOSIT: NearToken = NearToken::from_near(3);
+
+/// NEP-141 metadata spec version
+pub const FT_METADATA_SPEC: &str = "ft-1.0.0";
+
+pub const GAS_FOR_TOKEN_INIT: Gas = Gas::from_tgas(20);
+pub const GAS_FOR_MINT: Gas = Gas::from_tgas(10);
+pub const GAS_FOR_BURN: Gas = Gas::from_tgas(10);
+pub const GAS_FOR_CALLBACK: Gas = Gas::from_tgas(10);
+
+/// NEP-148 metadata of a wrapped token
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct WrappedTokenMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub icon: Option<String>,
+    pub decimals: u8,
+}
+
+/// A wrapped token contract deployed for a Stellar asset
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct WrappedTokenInfo {
+    /// Hex-encoded Stellar asset id
+    pub asset_id: String,
+    pub account_id: AccountId,
+    pub metadata: WrappedTokenMetadata,
+    /// Set once the deployment promise has succeeded
+    pub deployed: bool,
+}
+
+/// Wrapped token methods reserved for the bridge
+#[allow(dead_code)]
+#[ext_contract(ext_wrapped_token)]
+pub trait WrappedTokenContract {
+    fn mint(&mut self, account_id: AccountId, amount: U128);
+    fn burn(&mut self, account_id: AccountId, amount: U128);
+}
+
+/// Token sub-account for `symbol` under the bridge account
+pub fn token_account_id(symbol: &str, bridge: &AccountId) -> AccountId {
+    assert!(
+        !symbol.is_empty() && symbol.chars().all(|c| c.is_ascii_alphanumeric()),
+        "Symbol must be alphanumeric"
+    );
+    format!(
+        "{}{}.{}",
+        symbol.to_ascii_lowercase(),
+        TOKEN_ACCOUNT_SUFFIX,
+        bridge
+    )
+    .parse()
+    .expect("Invalid token account id")
+}
+
+/// `new` arguments of the wrapped token contract
+pub fn token_init_args(metadata: &WrappedTokenMetadata) -> Vec<u8> {
+    near_sdk::serde_json::json!({
+        "metadata": {
+            "spec": FT_METADATA_SPEC,
+            "name": metadata.name,
+            "symbol": metadata.symbol,
+            "icon": metadata.icon,
+            "reference": null,
+            "reference_hash": null,
+            "decimals": metadata.decimals,
+        }
+    })
+    .to_string()
+    .into_bytes()
+}
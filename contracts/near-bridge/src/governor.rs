@@ -0,0 +1,73 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, AccountId};
+
+/// Length of the rolling rate-limit window (24 hours)
+pub const WINDOW_SECS: u64 = 86_400;
+
+/// Delay before a queued mint can be executed (24 hours)
+pub const QUEUE_DELAY_SECS: u64 = 86_400;
+
+/// Mints within the same hour share one `Flow` entry
+pub const FLOW_BUCKET_SECS: u64 = 3_600;
+
+/// Per-asset mint limits
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TokenLimit {
+    /// Maximum amount minted in any rolling 24h window
+    pub daily_limit: u128,
+    /// Mints of at least this amount are always queued
+    pub big_tx_threshold: u128,
+}
+
+/// Mints recorded in the rolling window, bucketed by hour
+#[derive(BorshSerialize, BorshDeserialize, Clone)]
+pub struct Flow {
+    pub timestamp: u64,
+    pub amount: u128,
+}
+
+/// A mint held back by the governor
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct QueuedMint {
+    pub asset_id: String,
+    pub recipient: AccountId,
+    pub amount: u128,
+    pub release_after: u64,
+}
+
+/// Current block time in seconds
+pub fn now_secs() -> u64 {
+    env::block_timestamp() / 1_000_000_000
+}
+
+/// Drop flows that have left the rolling window
+pub fn prune_flows(flows: Vec<Flow>) -> Vec<Flow> {
+    let now = now_secs();
+    flows
+        .into_iter()
+        .filter(|flow| flow.timestamp + WINDOW_SECS > now)
+        .collect()
+}
+
+/// Total amount of the given flows
+pub fn flows_total(flows: &[Flow]) -> u128 {
+    flows.iter().map(|flow| flow.amount).sum()
+}
+
+/// Add a mint of `amount` at the current time to `flows`
+pub fn record_flow(flows: &mut Vec<Flow>, amount: u128) {
+    let now = now_secs();
+    match flows.last_mut() {
+        Some(last) if last.timestamp / FLOW_BUCKET_SECS == now / FLOW_BUCKET_SECS => {
+            last.timestamp = now;
+            last.amount += amount;
+        }
+        _ => flows.push(Flow {
+            timestamp: now,
+            amount,
+        }),
+    }
+}
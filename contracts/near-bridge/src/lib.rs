@@ -1,7 +1,44 @@
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::{UnorderedMap, UnorderedSet};
-use near_sdk::{env, near_bindgen, AccountId, Balance, PanicOnDefault, Promise};
+use near_sdk::json_types::U128;
+use near_sdk::store::{IterableMap, IterableSet, Vector};
+use near_sdk::{
+    assert_one_yocto, env, near_bindgen, AccountId, BorshStorageKey, CryptoHash, NearToken,
+    PanicOnDefault, Promise, PromiseError, PromiseOrValue,
+};
 use near_sdk::serde::{Deserialize, Serialize};
+use bridge_shared::{
+    contract_address, parse_address, ChainId, GovernanceAction, GovernanceVAA, VAA,
+};
+
+mod escrow;
+mod factory;
+mod governor;
+mod migration;
+mod outbound;
+mod storage;
+mod timelock;
+mod views;
+
+pub use escrow::{LockRecord, NATIVE_TOKEN_ID};
+use escrow::{emit_lock_event, LockMessage};
+pub use factory::{WrappedTokenInfo, WrappedTokenMetadata, TOKEN_DEPLOY_DEPOSIT};
+use factory::{
+    ext_wrapped_token, token_account_id, token_init_args, GAS_FOR_BURN, GAS_FOR_CALLBACK,
+    GAS_FOR_MINT, GAS_FOR_TOKEN_INIT, TOKEN_CODE_KEY,
+};
+pub use governor::{QueuedMint, TokenLimit};
+use governor::{flows_total, now_secs, prune_flows, record_flow, Flow, QUEUE_DELAY_SECS};
+pub use migration::{
+    NearBridgeV1, PendingMigration, VersionedState, MIGRATION_BATCH, STATE_VERSION,
+};
+use migration::{write_state_version, GAS_FOR_MIGRATE};
+pub use outbound::OutboundMessage;
+use outbound::emit_burn_event;
+pub use storage::{PendingClaim, StorageBalance, StorageBalanceBounds};
+use storage::AccountStorage;
+pub use timelock::{ScheduledAction, TimelockAction, TIMELOCK_DELAY_SECS};
+pub use views::{AssetView, BridgeConfig, MAX_PAGE_SIZE};
+use views::page;
 
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone)]
 #[serde(crate = "near_sdk::serde")]
@@ -9,6 +46,71 @@ pub struct Guardian {
     pub pubkey: [u8; 32],
 }
 
+/// Emergency stop switches
+///
+/// `inbound` blocks `mint_wrapped` (Stellar -> NEAR),
+/// `outbound` blocks `burn_wrapped` and locks (NEAR -> Stellar).
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PauseState {
+    pub inbound: bool,
+    pub outbound: bool,
+}
+
+/// Storage prefixes of the contract collections
+#[derive(BorshStorageKey, BorshSerialize)]
+pub(crate) enum StorageKey {
+    ProcessedVaas,
+    WrappedBalances,
+    /// One legacy balance map per asset, keyed by the sha256 of its id
+    WrappedBalance { asset_hash: CryptoHash },
+    WrappedSupply,
+    TokenLimits,
+    GovernorFlows,
+    QueuedMints,
+    Locks,
+    Escrowed,
+    ScheduledActions,
+    WrappedTokens,
+    StorageAccounts,
+    PendingClaims,
+    OutboundMessages,
+    ProcessedByOrigin,
+    /// Nonces processed from one origin chain
+    ProcessedFromChain { chain: u8 },
+    OutboundBySender,
+    /// Outbound sequences of one sender, keyed by the sha256 of the account
+    SenderOutbound { account_hash: CryptoHash },
+    LockTokens,
+}
+
+/// Prefix of the legacy balance map of `asset_id`
+///
+/// Hashing gives every asset a prefix of the same length, so no asset's
+/// keys can run into another asset's map or into the outer map.
+pub(crate) fn wrapped_balance_prefix(asset_id: &str) -> StorageKey {
+    StorageKey::WrappedBalance {
+        asset_hash: env::sha256_array(asset_id.as_bytes()),
+    }
+}
+
+/// Prefix of `sender`'s outbound index
+fn sender_outbound_prefix(sender: &AccountId) -> StorageKey {
+    StorageKey::SenderOutbound {
+        account_hash: env::sha256_array(sender.as_bytes()),
+    }
+}
+
+/// 32-byte form of a lock or burn recipient; panics unless the chain is a remote
+/// chain from the registry and the address is valid there
+fn recipient_address(recipient_chain: u8, recipient: &str) -> [u8; 32] {
+    let chain = ChainId::try_from(recipient_chain)
+        .unwrap_or_else(|e| env::panic_str(&format!("Invalid recipient chain: {}", e)));
+    assert!(chain != ChainId::Near, "Recipient chain must be a remote chain");
+    parse_address(chain, recipient)
+        .unwrap_or_else(|e| env::panic_str(&format!("Invalid recipient: {}", e)))
+}
+
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 pub struct NearBridge {
@@ -20,15 +122,87 @@ pub struct NearBridge {
     
     /// Required signature quorum
     pub quorum: u32,
+
+    /// Number of guardian set updates executed
+    pub guardian_set_index: u32,
     
     /// Processed VAA nonces (replay protection)
-    pub processed_vaas: UnorderedSet<u64>,
+    pub processed_vaas: IterableSet<u64>,
+
+    /// Processed VAA nonces in processing order, per origin chain
+    pub processed_by_origin: IterableMap<u8, Vector<u64>>,
     
-    /// Wrapped token balances (asset_id -> account -> balance)
-    pub wrapped_balances: UnorderedMap<String, UnorderedMap<AccountId, Balance>>,
+    /// Legacy wrapped balances held by the bridge (asset_id -> account -> balance)
+    ///
+    /// New mints go to the asset's NEP-141 token; holders move these with
+    /// `migrate_legacy_balance`.
+    pub wrapped_balances: IterableMap<String, IterableMap<AccountId, u128>>,
     
     /// Total supply per wrapped asset
-    pub wrapped_supply: UnorderedMap<String, Balance>,
+    pub wrapped_supply: IterableMap<String, u128>,
+
+    /// Account allowed to pause (but not unpause) the bridge
+    pub pauser: Option<AccountId>,
+
+    /// Current pause switches
+    pub paused: PauseState,
+
+    /// Last consumed governance VAA sequence
+    pub governance_sequence: u64,
+
+    /// Governor limits per wrapped asset
+    pub token_limits: IterableMap<String, TokenLimit>,
+
+    /// Mints per asset within the current rolling window
+    pub governor_flows: IterableMap<String, Vec<Flow>>,
+
+    /// Mints held back by the governor (VAA nonce -> mint)
+    pub queued_mints: IterableMap<u64, QueuedMint>,
+
+    /// Latest lock nonce
+    pub lock_nonce: u64,
+
+    /// Locks of NEAR-side assets (nonce -> record)
+    pub locks: IterableMap<u64, LockRecord>,
+
+    /// Escrowed balance per token (NEP-141 account or NATIVE_TOKEN_ID)
+    pub escrowed: IterableMap<String, u128>,
+
+    /// Latest timelock id
+    pub timelock_nonce: u64,
+
+    /// Parameter changes waiting out the timelock (id -> action)
+    pub scheduled_actions: IterableMap<u64, ScheduledAction>,
+
+    /// NEP-141 token contracts per wrapped asset
+    pub wrapped_tokens: IterableMap<String, WrappedTokenInfo>,
+
+    /// NEP-145 storage deposits of registered accounts
+    pub storage_accounts: IterableMap<AccountId, AccountStorage>,
+
+    /// Bytes used by one storage registration
+    pub account_storage_usage: u64,
+
+    /// Mints rejected by the token contract (VAA nonce -> claim)
+    pub pending_claims: IterableMap<u64, PendingClaim>,
+
+    /// Sequence of the last outbound burn
+    pub outbound_sequence: u64,
+
+    /// Completed burns by outbound sequence
+    pub outbound_messages: IterableMap<u64, OutboundMessage>,
+
+    /// Outbound sequences per sender, oldest first
+    pub outbound_by_sender: IterableMap<AccountId, Vector<u64>>,
+
+    /// Code hash approved by an `Upgrade` governance VAA
+    pub approved_upgrade: Option<[u8; 32]>,
+
+    /// Version 1 collections not yet moved by `migrate_batch`
+    pub pending_migration: Option<PendingMigration>,
+
+    /// NEP-141 token contracts accepted by `ft_on_transfer`
+    pub lock_tokens: IterableSet<AccountId>,
 }
 
 #[near_bindgen]
@@ -43,118 +217,664 @@ impl NearBridge {
             .map(|pk| Guardian { pubkey: *pk })
             .collect();
 
-        Self {
+        let mut this = Self {
             owner,
             guardians: guardian_list,
             quorum,
-            processed_vaas: UnorderedSet::new(b"p".to_vec()),
-            wrapped_balances: UnorderedMap::new(b"b".to_vec()),
-            wrapped_supply: UnorderedMap::new(b"s".to_vec()),
+            guardian_set_index: 0,
+            processed_vaas: IterableSet::new(StorageKey::ProcessedVaas),
+            processed_by_origin: IterableMap::new(StorageKey::ProcessedByOrigin),
+            wrapped_balances: IterableMap::new(StorageKey::WrappedBalances),
+            wrapped_supply: IterableMap::new(StorageKey::WrappedSupply),
+            pauser: None,
+            paused: PauseState::default(),
+            governance_sequence: 0,
+            token_limits: IterableMap::new(StorageKey::TokenLimits),
+            governor_flows: IterableMap::new(StorageKey::GovernorFlows),
+            queued_mints: IterableMap::new(StorageKey::QueuedMints),
+            lock_nonce: 0,
+            locks: IterableMap::new(StorageKey::Locks),
+            escrowed: IterableMap::new(StorageKey::Escrowed),
+            timelock_nonce: 0,
+            scheduled_actions: IterableMap::new(StorageKey::ScheduledActions),
+            wrapped_tokens: IterableMap::new(StorageKey::WrappedTokens),
+            storage_accounts: IterableMap::new(StorageKey::StorageAccounts),
+            account_storage_usage: 0,
+            pending_claims: IterableMap::new(StorageKey::PendingClaims),
+            outbound_sequence: 0,
+            outbound_messages: IterableMap::new(StorageKey::OutboundMessages),
+            outbound_by_sender: IterableMap::new(StorageKey::OutboundBySender),
+            approved_upgrade: None,
+            pending_migration: None,
+            lock_tokens: IterableSet::new(StorageKey::LockTokens),
+        };
+        this.measure_account_storage_usage();
+        write_state_version();
+        this
+    }
+
+    /// Move the stored state to the current layout
+    ///
+    /// Called by `upgrade` right after the new code is deployed; a no-op
+    /// when the state is already current.
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        let state = VersionedState::read().into_current();
+        write_state_version();
+        state
+    }
+
+    /// Move the next `MIGRATION_BATCH` entries of a version 1 state
+    ///
+    /// `migrate` only switches the layout, so a state of any size upgrades
+    /// within one call; call this until it returns true. Transfers stay
+    /// paused until then and afterwards until the owner unpauses, so a pause
+    /// set during the migration is never lifted here. Anyone may call.
+    ///
+    /// # Returns
+    /// Whether the migration is complete
+    pub fn migrate_batch(&mut self) -> bool {
+        let Some(mut pending) = self.pending_migration.take() else {
+            return true;
+        };
+        if !pending.step(self, MIGRATION_BATCH) {
+            self.pending_migration = Some(pending);
+            return false;
         }
+
+        env::log_str("State migration complete; unpause to resume transfers");
+        true
+    }
+
+    /// Deploy new contract code and migrate the state
+    ///
+    /// The wasm is passed as the raw call input. The owner may upgrade at
+    /// any time; anyone else may deploy code whose sha256 the guardians
+    /// approved with an `Upgrade` governance VAA.
+    pub fn upgrade(&mut self) -> Promise {
+        let code = env::input().expect("Missing contract code");
+        assert!(
+            env::predecessor_account_id() == self.owner
+                || self.approved_upgrade == Some(env::sha256_array(&code)),
+            "Upgrade not authorized"
+        );
+        self.approved_upgrade = None;
+
+        env::log_str("Contract upgrade deployed");
+        Promise::new(env::current_account_id())
+            .deploy_contract(code)
+            .function_call(
+                "migrate".to_string(),
+                Vec::new(),
+                NearToken::from_near(0),
+                GAS_FOR_MIGRATE,
+            )
     }
 
     /// Mint wrapped tokens based on verified VAA from source chain
     /// 
     /// # Arguments
     /// * `vaa_json` - JSON-serialized VAA with guardian signatures
-    pub fn mint_wrapped(&mut self, vaa_json: String) {
-        // Parse VAA
-        // In production: deserialize from JSON, verify structure
-        // let vaa: VAA = serde_json::from_str(&vaa_json).expect("Invalid VAA");
-        
-        // TODO: Implement full VAA verification
-        // 1. Compute digest from VAA fields
-        // 2. Verify guardian signatures against digest
-        // 3. Check quorum is met
-        // 4. Verify destination_chain == NEAR and destination_contract == this contract
-        
-        // Placeholder values (replace with actual parsing)
-        let vaa_nonce = 1u64;
-        let asset_id = "stellar_asset_123".to_string();
-        let recipient = env::predecessor_account_id();
-        let amount: Balance = 1000;
+    /// * `recipient` - Named recipient account whose `contract_address` hash
+    ///   is the VAA recipient; if omitted the VAA recipient is read as an
+    ///   implicit account (hex of the 32 bytes)
+    ///
+    /// The wrapped asset id is the hex-encoded VAA asset id.
+    pub fn mint_wrapped(&mut self, vaa_json: String, recipient: Option<AccountId>) {
+        assert!(!self.paused.inbound, "Inbound transfers paused");
+
+        let vaa = VAA::from_bytes(vaa_json.as_bytes()).expect("Invalid VAA");
+        assert_eq!(vaa.version, 1, "Unsupported VAA version");
+        assert_eq!(vaa.origin_chain, ChainId::Stellar, "Wrong origin chain");
+        assert_eq!(vaa.destination_chain, ChainId::Near, "Wrong destination chain");
+        assert_eq!(
+            vaa.destination_contract,
+            contract_address(env::current_account_id().as_str()),
+            "Wrong destination contract"
+        );
+        assert!(vaa.amount > 0, "Amount must be positive");
+        assert!(
+            vaa.verify_signatures(&self.get_guardians(), self.quorum as usize),
+            "Insufficient guardian signatures"
+        );
+
+        let recipient = match recipient {
+            Some(account) => {
+                assert_eq!(
+                    contract_address(account.as_str()),
+                    vaa.recipient,
+                    "Recipient does not match VAA"
+                );
+                account
+            }
+            None => hex::encode(vaa.recipient)
+                .parse()
+                .expect("Invalid implicit recipient"),
+        };
+        let vaa_nonce = vaa.nonce;
+        let asset_id = hex::encode(vaa.asset_id);
+        let amount: u128 = vaa.amount;
 
         // Replay protection
         assert!(
             !self.processed_vaas.contains(&vaa_nonce),
             "VAA already processed"
         );
-        self.processed_vaas.insert(&vaa_nonce);
+        self.mark_processed(vaa.origin_chain, vaa_nonce);
 
-        // Mint wrapped tokens
-        let mut balances = self
-            .wrapped_balances
-            .get(&asset_id)
-            .unwrap_or_else(|| UnorderedMap::new(format!("b:{}", asset_id).as_bytes().to_vec()));
+        // Large or over-limit mints wait out the governor delay
+        if self.governor_should_queue(&asset_id, amount) {
+            let queued = QueuedMint {
+                asset_id,
+                recipient,
+                amount,
+                release_after: now_secs() + QUEUE_DELAY_SECS,
+            };
+            env::log_str(&format!(
+                "Mint queued by governor: nonce {} release after {}",
+                vaa_nonce, queued.release_after
+            ));
+            self.queued_mints.insert(vaa_nonce, queued);
+            return;
+        }
 
-        let current_balance = balances.get(&recipient).unwrap_or(0);
-        balances.insert(&recipient, &(current_balance + amount));
-        self.wrapped_balances.insert(&asset_id, &balances);
+        self.internal_mint(vaa_nonce, &asset_id, &recipient, amount);
+    }
 
-        // Update total supply
-        let current_supply = self.wrapped_supply.get(&asset_id).unwrap_or(0);
-        self.wrapped_supply
-            .insert(&asset_id, &(current_supply + amount));
+    /// Execute a mint queued by the governor once its delay has passed
+    ///
+    /// # Arguments
+    /// * `nonce` - VAA nonce of the queued mint
+    pub fn execute_queued_mint(&mut self, nonce: u64) {
+        assert!(!self.paused.inbound, "Inbound transfers paused");
 
-        // Emit event
-        env::log_str(&format!(
-            "Minted {} of {} to {}",
-            amount, asset_id, recipient
-        ));
+        let queued = self.queued_mints.get(&nonce).expect("Mint not queued");
+        assert!(now_secs() >= queued.release_after, "Mint still delayed");
+
+        let queued = self.queued_mints.remove(&nonce).unwrap();
+        self.internal_mint(nonce, &queued.asset_id, &queued.recipient, queued.amount);
+    }
+
+    /// Resolve a token mint; a failed mint is held for `claim_wrapped`
+    ///
+    /// Mints fail when the recipient has not registered storage with the
+    /// token, so the bridge never pays for recipients' token storage.
+    #[private]
+    pub fn on_mint_complete(
+        &mut self,
+        nonce: u64,
+        asset_id: String,
+        recipient: AccountId,
+        amount: U128,
+        #[callback_result] result: Result<(), PromiseError>,
+    ) -> bool {
+        if result.is_ok() {
+            env::log_str(&format!(
+                "Minted {} of {} to {}",
+                amount.0, asset_id, recipient
+            ));
+            return true;
+        }
+
+        *self.wrapped_supply.entry(asset_id.clone()).or_insert(0) -= amount.0;
+        self.pending_claims.insert(
+            nonce,
+            PendingClaim {
+                asset_id,
+                recipient,
+                amount: amount.0,
+            },
+        );
+        env::log_str(&format!("Mint failed, held for claim: nonce {}", nonce));
+        false
+    }
+
+    /// Retry a mint held after the token contract rejected it
+    ///
+    /// The recipient must first register storage with the token. Anyone may
+    /// claim; the tokens always go to the recipient from the VAA.
+    pub fn claim_wrapped(&mut self, nonce: u64) {
+        assert!(!self.paused.inbound, "Inbound transfers paused");
+
+        let claim = self.pending_claims.remove(&nonce).expect("Nothing to claim");
+        *self.wrapped_supply.entry(claim.asset_id.clone()).or_insert(0) += claim.amount;
+
+        self.mint_through_token(nonce, &claim.asset_id, &claim.recipient, claim.amount);
+    }
+
+    /// Get a mint held for claim (view only)
+    pub fn get_pending_claim(&self, nonce: u64) -> Option<PendingClaim> {
+        self.pending_claims.get(&nonce).cloned()
     }
 
     /// Burn wrapped tokens to unlock on source chain
-    /// 
+    ///
+    /// Requires exactly 1 yoctoNEAR; the bridge burns from the caller's
+    /// balance in the asset's token contract. The recipient is checked
+    /// before burning, since a mistyped address would lose the funds.
+    ///
     /// # Arguments
     /// * `asset_id` - Wrapped asset identifier
     /// * `amount` - Amount to burn
     /// * `recipient_chain` - Destination chain (1 = Stellar)
-    /// * `recipient` - Recipient address on destination chain (hex string)
+    /// * `recipient` - Recipient address on destination chain (strkey for Stellar)
+    #[payable]
     pub fn burn_wrapped(
         &mut self,
         asset_id: String,
-        amount: Balance,
+        amount: u128,
         recipient_chain: u8,
         recipient: String,
-    ) {
-        let sender = env::predecessor_account_id();
+    ) -> Promise {
+        assert!(!self.paused.outbound, "Outbound transfers paused");
+        assert_one_yocto();
+        assert!(amount > 0, "Amount must be positive");
+        recipient_address(recipient_chain, &recipient);
 
-        // Get sender's balance
-        let mut balances = self
-            .wrapped_balances
-            .get(&asset_id)
-            .expect("Asset not found");
+        let sender = env::predecessor_account_id();
+        let token = self.wrapped_token_account(&asset_id);
 
-        let current_balance = balances.get(&sender).expect("Insufficient balance");
-        assert!(current_balance >= amount, "Insufficient balance");
+        ext_wrapped_token::ext(token)
+            .with_static_gas(GAS_FOR_BURN)
+            .burn(sender.clone(), U128(amount))
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_CALLBACK)
+                    .on_burn_complete(asset_id, sender, U128(amount), recipient_chain, recipient),
+            )
+    }
 
-        // Burn tokens
-        balances.insert(&sender, &(current_balance - amount));
-        self.wrapped_balances.insert(&asset_id, &balances);
+    /// Resolve a token burn; record the outbound message and emit the
+    /// burn event for the relayer
+    #[private]
+    pub fn on_burn_complete(
+        &mut self,
+        asset_id: String,
+        sender: AccountId,
+        amount: U128,
+        recipient_chain: u8,
+        recipient: String,
+        #[callback_result] result: Result<(), PromiseError>,
+    ) -> bool {
+        if result.is_err() {
+            env::log_str(&format!("Burn failed for {} of {}", sender, asset_id));
+            return false;
+        }
 
         // Update supply
-        let current_supply = self.wrapped_supply.get(&asset_id).unwrap_or(0);
-        self.wrapped_supply
-            .insert(&asset_id, &(current_supply - amount));
+        *self.wrapped_supply.entry(asset_id.clone()).or_insert(0) -= amount.0;
+
+        // Record the burn; the relayer observes the event and creates the VAA
+        self.outbound_sequence += 1;
+        let message = OutboundMessage {
+            sequence: self.outbound_sequence,
+            asset_id,
+            amount: amount.0,
+            sender,
+            recipient_chain,
+            recipient_address: hex::encode(recipient_address(recipient_chain, &recipient)),
+            recipient,
+            timestamp: now_secs(),
+            block_height: env::block_height(),
+        };
+        emit_burn_event(&message);
+        self.outbound_by_sender
+            .entry(message.sender.clone())
+            .or_insert_with(|| Vector::new(sender_outbound_prefix(&message.sender)))
+            .push(message.sequence);
+        self.outbound_messages.insert(message.sequence, message);
+        true
+    }
+
+    /// Get an outbound burn record (view only)
+    pub fn get_outbound_message(&self, sequence: u64) -> Option<OutboundMessage> {
+        self.outbound_messages.get(&sequence).cloned()
+    }
 
-        // Emit burn event (relayer will observe this and create VAA for destination chain)
+    /// List burns by sequence (view only)
+    ///
+    /// # Arguments
+    /// * `from_sequence` - First sequence to include
+    /// * `limit` - Maximum number of messages (capped at MAX_PAGE_SIZE)
+    ///
+    /// # Returns
+    /// OutboundMessages; continue from the last sequence + 1
+    pub fn list_outbound(&self, from_sequence: u64, limit: u32) -> Vec<OutboundMessage> {
+        let from_sequence = from_sequence.max(1);
+        let end = from_sequence
+            .saturating_add(u64::from(limit.min(MAX_PAGE_SIZE)))
+            .min(self.outbound_sequence.saturating_add(1));
+        (from_sequence..end)
+            .filter_map(|sequence| self.get_outbound_message(sequence))
+            .collect()
+    }
+
+    /// Upload the wrapped token wasm used by `deploy_wrapped_token` (owner only)
+    ///
+    /// The wasm is passed as the raw call input, not as JSON arguments.
+    pub fn set_token_code(&mut self) {
+        self.assert_owner();
+        let code = env::input().expect("Missing token code");
+        env::storage_write(TOKEN_CODE_KEY, &code);
+    }
+
+    /// Deploy the NEP-141 token for a wrapped asset (owner only)
+    ///
+    /// Creates `<symbol>-stellar.<bridge account>` funded with the attached
+    /// deposit, which must be at least `TOKEN_DEPLOY_DEPOSIT`. Mints of the
+    /// asset go to the token once the deployment has succeeded.
+    #[payable]
+    pub fn deploy_wrapped_token(
+        &mut self,
+        asset_id: String,
+        metadata: WrappedTokenMetadata,
+    ) -> Promise {
+        self.assert_owner();
+        let deposit = env::attached_deposit();
+        assert!(deposit >= TOKEN_DEPLOY_DEPOSIT, "Insufficient deposit for token deployment");
+        assert!(
+            !self.wrapped_tokens.contains_key(&asset_id),
+            "Wrapped token already exists"
+        );
+        let code = env::storage_read(TOKEN_CODE_KEY).expect("Token code not set");
+
+        let account_id = token_account_id(&metadata.symbol, &env::current_account_id());
+        assert!(
+            self.wrapped_tokens.values().all(|token| token.account_id != account_id),
+            "Token account already in use"
+        );
+
+        let init_args = token_init_args(&metadata);
+        self.wrapped_tokens.insert(
+            asset_id.clone(),
+            WrappedTokenInfo {
+                asset_id: asset_id.clone(),
+                account_id: account_id.clone(),
+                metadata,
+                deployed: false,
+            },
+        );
+
+        Promise::new(account_id)
+            .create_account()
+            .transfer(deposit)
+            .deploy_contract(code)
+            .function_call(
+                "new".to_string(),
+                init_args,
+                NearToken::from_near(0),
+                GAS_FOR_TOKEN_INIT,
+            )
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_CALLBACK)
+                    .on_token_deployed(asset_id, deposit),
+            )
+    }
+
+    /// Resolve a token deployment; on failure forget the token and refund the owner
+    #[private]
+    pub fn on_token_deployed(
+        &mut self,
+        asset_id: String,
+        deposit: NearToken,
+        #[callback_result] result: Result<(), PromiseError>,
+    ) -> bool {
+        assert!(
+            self.wrapped_tokens.contains_key(&asset_id),
+            "Wrapped token not found"
+        );
+
+        if result.is_err() {
+            self.wrapped_tokens.remove(&asset_id);
+            env::log_str(&format!("Token deployment failed for {}", asset_id));
+            Promise::new(self.owner.clone()).transfer(deposit);
+            return false;
+        }
+
+        let token = self.wrapped_tokens.get_mut(&asset_id).unwrap();
+        token.deployed = true;
         env::log_str(&format!(
-            "Burned {} of {} from {} to chain {} recipient {}",
-            amount, asset_id, sender, recipient_chain, recipient
+            "Wrapped token {} deployed for {}",
+            token.account_id, asset_id
         ));
+        true
+    }
+
+    /// Move the caller's legacy balance into the asset's token contract
+    ///
+    /// The caller must be registered with the token; on failure the legacy
+    /// balance is restored.
+    pub fn migrate_legacy_balance(&mut self, asset_id: String) -> Promise {
+        let account = env::predecessor_account_id();
+        let token = self.wrapped_token_account(&asset_id);
+
+        let amount = self
+            .wrapped_balances
+            .get_mut(&asset_id)
+            .expect("Asset not found")
+            .remove(&account)
+            .expect("No legacy balance");
+
+        ext_wrapped_token::ext(token)
+            .with_static_gas(GAS_FOR_MINT)
+            .mint(account.clone(), U128(amount))
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_CALLBACK)
+                    .on_legacy_migrated(asset_id, account, U128(amount)),
+            )
+    }
+
+    /// Resolve a legacy balance migration; restore the balance on failure
+    #[private]
+    pub fn on_legacy_migrated(
+        &mut self,
+        asset_id: String,
+        account: AccountId,
+        amount: U128,
+        #[callback_result] result: Result<(), PromiseError>,
+    ) -> bool {
+        if result.is_ok() {
+            return true;
+        }
+
+        let balances = self
+            .wrapped_balances
+            .get_mut(&asset_id)
+            .expect("Asset not found");
+        *balances.entry(account.clone()).or_insert(0) += amount.0;
+        env::log_str(&format!("Legacy balance migration failed for {}", account));
+        false
+    }
+
+    /// Register an account, or add to its storage deposit (NEP-145)
+    ///
+    /// Registered accounts pay for the records they create, such as locks.
+    /// With `registration_only`, anything above the minimum is refunded.
+    #[payable]
+    pub fn storage_deposit(
+        &mut self,
+        account_id: Option<AccountId>,
+        registration_only: Option<bool>,
+    ) -> StorageBalance {
+        let amount = env::attached_deposit();
+        let account_id = account_id.unwrap_or_else(env::predecessor_account_id);
+        let registration_only = registration_only.unwrap_or(false);
+        let min = self.storage_balance_bounds().min;
+
+        if let Some(storage) = self.storage_accounts.get_mut(&account_id) {
+            if registration_only {
+                Self::refund_deposit(amount);
+            } else {
+                storage.deposit = storage.deposit.saturating_add(amount);
+            }
+            return storage.to_balance();
+        }
+
+        assert!(amount >= min, "Insufficient storage deposit");
+        let deposit = if registration_only { min } else { amount };
+        Self::refund_deposit(amount.saturating_sub(deposit));
+
+        let storage = AccountStorage {
+            deposit,
+            used_bytes: self.account_storage_usage,
+        };
+        let balance = storage.to_balance();
+        self.storage_accounts.insert(account_id, storage);
+        balance
+    }
+
+    /// Withdraw unused storage deposit (NEP-145)
+    ///
+    /// Withdraws everything available when `amount` is omitted.
+    #[payable]
+    pub fn storage_withdraw(&mut self, amount: Option<NearToken>) -> StorageBalance {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let storage = self
+            .storage_accounts
+            .get_mut(&account_id)
+            .expect("Storage not registered");
+
+        let available = storage.available();
+        let amount = amount.unwrap_or(available);
+        assert!(amount <= available, "Withdrawal exceeds available storage balance");
+
+        storage.deposit = storage.deposit.saturating_sub(amount);
+        let balance = storage.to_balance();
+        if !amount.is_zero() {
+            Promise::new(account_id).transfer(amount);
+        }
+
+        balance
+    }
+
+    /// Close a storage registration and refund the deposit (NEP-145)
+    ///
+    /// Accounts that still have records charged to them cannot unregister;
+    /// `force` is not supported since bridge records cannot be dropped.
+    #[payable]
+    pub fn storage_unregister(&mut self, force: Option<bool>) -> bool {
+        assert_one_yocto();
+        assert!(!force.unwrap_or(false), "Forced unregister not supported");
+
+        let account_id = env::predecessor_account_id();
+        let storage = match self.storage_accounts.get(&account_id) {
+            Some(storage) => storage,
+            None => return false,
+        };
+        assert_eq!(
+            storage.used_bytes, self.account_storage_usage,
+            "Storage still in use"
+        );
+
+        let refund = storage.deposit;
+        self.storage_accounts.remove(&account_id);
+        Promise::new(account_id).transfer(refund);
+        true
+    }
+
+    /// Minimum deposit for a registration (NEP-145)
+    pub fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+        StorageBalanceBounds {
+            min: env::storage_byte_cost().saturating_mul(self.account_storage_usage as u128),
+            max: None,
+        }
+    }
+
+    /// Storage balance of an account, if registered (NEP-145)
+    pub fn storage_balance_of(&self, account_id: AccountId) -> Option<StorageBalance> {
+        self.storage_accounts
+            .get(&account_id)
+            .map(AccountStorage::to_balance)
+    }
+
+    /// Get the token contract of a wrapped asset (view only)
+    pub fn get_wrapped_token(&self, asset_id: String) -> Option<WrappedTokenInfo> {
+        self.wrapped_tokens.get(&asset_id).cloned()
+    }
+
+    /// NEP-141 receiver: lock transferred tokens for transfer to Stellar
+    ///
+    /// # Arguments
+    /// * `sender_id` - Account that called `ft_transfer_call`
+    /// * `amount` - Amount transferred
+    /// * `msg` - JSON `{"recipient_chain": 1, "recipient": "G..."}`
+    ///
+    /// Only tokens added with `set_lock_token` are accepted: `sender_id` is
+    /// trusted only from a token that actually moved the sender's balance,
+    /// and the lock's storage is charged to that sender.
+    ///
+    /// Panicking makes the token contract refund the whole transfer.
+    pub fn ft_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        amount: U128,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        let token = env::predecessor_account_id();
+        assert!(self.lock_tokens.contains(&token), "Token not accepted for locking");
+        let message: LockMessage =
+            near_sdk::serde_json::from_str(&msg).expect("Invalid lock message");
+        let token = token.to_string();
+
+        self.internal_lock(
+            token,
+            amount.0,
+            sender_id,
+            message.recipient_chain,
+            message.recipient,
+        );
+
+        // Keep the whole amount
+        PromiseOrValue::Value(U128(0))
+    }
+
+    /// Lock the attached NEAR for transfer to Stellar
+    ///
+    /// # Arguments
+    /// * `recipient_chain` - Destination chain (1 = Stellar)
+    /// * `recipient` - Recipient address on destination chain
+    ///
+    /// # Returns
+    /// The lock nonce
+    #[payable]
+    pub fn lock_near(&mut self, recipient_chain: u8, recipient: String) -> u64 {
+        self.internal_lock(
+            NATIVE_TOKEN_ID.to_string(),
+            env::attached_deposit().as_yoctonear(),
+            env::predecessor_account_id(),
+            recipient_chain,
+            recipient,
+        )
+    }
+
+    /// Get a lock record by nonce
+    pub fn get_lock(&self, nonce: u64) -> Option<LockRecord> {
+        self.locks.get(&nonce).cloned()
+    }
+
+    /// Get the escrowed balance of a token (NEP-141 account or NATIVE_TOKEN_ID)
+    pub fn get_escrowed_balance(&self, token: String) -> u128 {
+        self.escrowed.get(&token).copied().unwrap_or(0)
     }
 
-    /// Get wrapped token balance
-    pub fn balance_of(&self, asset_id: String, account: AccountId) -> Balance {
+    /// Get a legacy wrapped balance held by the bridge
+    ///
+    /// Balances minted to token contracts are read with `ft_balance_of`.
+    pub fn balance_of(&self, asset_id: String, account: AccountId) -> u128 {
         self.wrapped_balances
             .get(&asset_id)
             .and_then(|balances| balances.get(&account))
+            .copied()
             .unwrap_or(0)
     }
 
     /// Get total supply of wrapped asset
-    pub fn total_supply(&self, asset_id: String) -> Balance {
-        self.wrapped_supply.get(&asset_id).unwrap_or(0)
+    pub fn total_supply(&self, asset_id: String) -> u128 {
+        self.wrapped_supply.get(&asset_id).copied().unwrap_or(0)
     }
 
     /// Check if VAA has been processed
@@ -162,8 +882,10 @@ impl NearBridge {
         self.processed_vaas.contains(&nonce)
     }
 
-    /// Update guardians (owner only)
-    pub fn update_guardians(&mut self, new_guardians: Vec<[u8; 32]>, new_quorum: u32) {
+    /// Schedule a guardian set update (owner only)
+    ///
+    /// Returns the timelock id; run `execute_scheduled` after the delay.
+    pub fn update_guardians(&mut self, new_guardians: Vec<[u8; 32]>, new_quorum: u32) -> u64 {
         assert_eq!(
             env::predecessor_account_id(),
             self.owner,
@@ -174,58 +896,1607 @@ impl NearBridge {
             "Invalid quorum"
         );
 
-        self.guardians = new_guardians
-            .iter()
-            .map(|pk| Guardian { pubkey: *pk })
-            .collect();
-        self.quorum = new_quorum;
+        self.schedule(TimelockAction::UpdateGuardians {
+            guardians: new_guardians,
+            quorum: new_quorum,
+        })
+    }
+
+    /// Execute a scheduled parameter change once its delay has passed
+    ///
+    /// Anyone may execute; guardians can cancel before then with a
+    /// `CancelTimelock` governance VAA.
+    pub fn execute_scheduled(&mut self, id: u64) {
+        let scheduled = self.scheduled_actions.get(&id).expect("Action not scheduled");
+        assert!(now_secs() >= scheduled.eta, "Action still timelocked");
+        let scheduled = self.scheduled_actions.remove(&id).unwrap();
+
+        match scheduled.action {
+            TimelockAction::UpdateGuardians { guardians, quorum } => {
+                self.guardians = guardians
+                    .iter()
+                    .map(|pk| Guardian { pubkey: *pk })
+                    .collect();
+                self.quorum = quorum;
+                self.guardian_set_index += 1;
+                env::log_str(&format!(
+                    "Guardians updated: {} guardians, quorum {}",
+                    self.guardians.len(),
+                    quorum
+                ));
+            }
+            TimelockAction::SetTokenLimit {
+                asset_id,
+                daily_limit,
+                big_tx_threshold,
+            } => {
+                self.token_limits.insert(
+                    asset_id.clone(),
+                    TokenLimit {
+                        daily_limit,
+                        big_tx_threshold,
+                    },
+                );
+                env::log_str(&format!("Token limit set for {}", asset_id));
+            }
+        }
+    }
+
+    /// Get a scheduled parameter change (view only)
+    pub fn get_scheduled(&self, id: u64) -> Option<ScheduledAction> {
+        self.scheduled_actions.get(&id).cloned()
     }
 
     /// Get guardians list (view only)
     pub fn get_guardians(&self) -> Vec<[u8; 32]> {
         self.guardians.iter().map(|g| g.pubkey).collect()
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use near_sdk::test_utils::VMContextBuilder;
-    use near_sdk::{testing_env, VMContext};
+    /// Get the bridge settings (view only)
+    pub fn get_config(&self) -> BridgeConfig {
+        BridgeConfig {
+            owner: self.owner.clone(),
+            pauser: self.pauser.clone(),
+            guardians: self.get_guardians(),
+            quorum: self.quorum,
+            guardian_set_index: self.guardian_set_index,
+            paused: self.paused.clone(),
+            storage_deposit_min: self.storage_balance_bounds().min,
+            token_deploy_deposit: TOKEN_DEPLOY_DEPOSIT,
+        }
+    }
 
-    fn get_context(predecessor: AccountId) -> VMContext {
-        VMContextBuilder::new()
-            .predecessor_account_id(predecessor)
-            .build()
+    /// List wrapped assets in deployment order (view only)
+    ///
+    /// # Arguments
+    /// * `cursor` - Position to start at (0 = first asset)
+    /// * `limit` - Maximum number of assets (capped at MAX_PAGE_SIZE)
+    ///
+    /// # Returns
+    /// AssetViews; continue from `cursor` + number returned
+    pub fn list_assets(&self, cursor: u32, limit: u32) -> Vec<AssetView> {
+        let range = page(cursor, limit, self.wrapped_tokens.len());
+        self.wrapped_tokens
+            .values()
+            .skip(range.start as usize)
+            .take(range.len())
+            .map(|token| AssetView {
+                total_supply: self.total_supply(token.asset_id.clone()),
+                token: token.clone(),
+            })
+            .collect()
     }
 
-    #[test]
-    fn test_initialization() {
-        let context = get_context("owner.near".parse().unwrap());
-        testing_env!(context);
+    /// List legacy balance holders of an asset (view only)
+    ///
+    /// Only balances still held by the bridge are listed. Balances minted to
+    /// a deployed token live in that token contract, whose NEP-141 accounts
+    /// map cannot be enumerated; index its `ft_mint` and `ft_transfer`
+    /// events to list those holders.
+    ///
+    /// # Returns
+    /// (account, balance) pairs; continue from `cursor` + number returned
+    pub fn list_holders(
+        &self,
+        asset_id: String,
+        cursor: u32,
+        limit: u32,
+    ) -> Vec<(AccountId, u128)> {
+        let Some(balances) = self.wrapped_balances.get(&asset_id) else {
+            return Vec::new();
+        };
+        let range = page(cursor, limit, balances.len());
+        balances
+            .iter()
+            .skip(range.start as usize)
+            .take(range.len())
+            .map(|(account, balance)| (account.clone(), *balance))
+            .collect()
+    }
 
-        let guardians = vec![[1u8; 32], [2u8; 32]];
-        let contract = NearBridge::new("owner.near".parse().unwrap(), guardians, 2);
+    /// List VAA nonces processed from an origin chain, in processing order
+    /// (view only)
+    ///
+    /// # Returns
+    /// Nonces; continue from `cursor` + number returned
+    pub fn list_processed(&self, origin_chain: u8, cursor: u32, limit: u32) -> Vec<u64> {
+        let Some(nonces) = self.processed_by_origin.get(&origin_chain) else {
+            return Vec::new();
+        };
+        page(cursor, limit, nonces.len())
+            .filter_map(|index| nonces.get(index).copied())
+            .collect()
+    }
 
-        assert_eq!(contract.guardians.len(), 2);
-        assert_eq!(contract.quorum, 2);
+    /// List a sender's burns, oldest first (view only)
+    ///
+    /// # Returns
+    /// OutboundMessages; continue from `cursor` + number returned
+    pub fn list_outbound_by_sender(
+        &self,
+        sender: AccountId,
+        cursor: u32,
+        limit: u32,
+    ) -> Vec<OutboundMessage> {
+        let Some(sequences) = self.outbound_by_sender.get(&sender) else {
+            return Vec::new();
+        };
+        page(cursor, limit, sequences.len())
+            .filter_map(|index| sequences.get(index))
+            .filter_map(|sequence| self.get_outbound_message(*sequence))
+            .collect()
     }
 
-    #[test]
-    fn test_balance_of() {
-        let context = get_context("owner.near".parse().unwrap());
-        testing_env!(context);
+    /// Accept or stop accepting locks of a NEP-141 token (owner only)
+    pub fn set_lock_token(&mut self, token: AccountId, accepted: bool) {
+        self.assert_owner();
+        if accepted {
+            self.lock_tokens.insert(token);
+        } else {
+            self.lock_tokens.remove(&token);
+        }
+    }
 
-        let contract = NearBridge::new(
-            "owner.near".parse().unwrap(),
-            vec![[1u8; 32]],
-            1,
+    /// Check whether `ft_on_transfer` accepts a token (view only)
+    pub fn is_lock_token(&self, token: AccountId) -> bool {
+        self.lock_tokens.contains(&token)
+    }
+
+    /// Set the pauser role (owner only)
+    pub fn set_pauser(&mut self, pauser: Option<AccountId>) {
+        self.assert_owner();
+        self.pauser = pauser;
+    }
+
+    /// Pause inbound and/or outbound transfers (pauser or owner)
+    ///
+    /// Switches that are already paused stay paused; unpausing requires `unpause`.
+    pub fn pause(&mut self, inbound: bool, outbound: bool) {
+        let caller = env::predecessor_account_id();
+        assert!(
+            caller == self.owner || Some(&caller) == self.pauser.as_ref(),
+            "Not authorized to pause"
         );
 
-        let balance = contract.balance_of(
-            "test_asset".to_string(),
-            "user.near".parse().unwrap(),
+        let state = PauseState {
+            inbound: self.paused.inbound || inbound,
+            outbound: self.paused.outbound || outbound,
+        };
+        self.set_pause_state(state);
+    }
+
+    /// Unpause inbound and/or outbound transfers (owner only)
+    pub fn unpause(&mut self, inbound: bool, outbound: bool) {
+        self.assert_owner();
+
+        let state = PauseState {
+            inbound: self.paused.inbound && !inbound,
+            outbound: self.paused.outbound && !outbound,
+        };
+        self.set_pause_state(state);
+    }
+
+    /// Execute a guardian-signed governance VAA
+    ///
+    /// # Arguments
+    /// * `vaa_json` - JSON-serialized GovernanceVAA with guardian signatures
+    pub fn submit_governance(&mut self, vaa_json: String) {
+        let vaa = GovernanceVAA::from_bytes(vaa_json.as_bytes())
+            .unwrap_or_else(|_| env::panic_str("Invalid governance VAA"));
+
+        assert_eq!(vaa.target_chain, ChainId::Near, "Wrong target chain");
+        assert_eq!(
+            vaa.target_contract,
+            contract_address(env::current_account_id().as_str()),
+            "Wrong target contract"
         );
-        assert_eq!(balance, 0);
+        assert!(
+            vaa.sequence > self.governance_sequence,
+            "Governance VAA already consumed"
+        );
+        assert!(
+            vaa.verify_signatures(&self.get_guardians(), self.quorum as usize),
+            "Insufficient guardian signatures"
+        );
+        self.governance_sequence = vaa.sequence;
+
+        match vaa.action {
+            GovernanceAction::SetPause { inbound, outbound } => {
+                self.set_pause_state(PauseState { inbound, outbound });
+            }
+            GovernanceAction::CancelQueuedTransfer { nonce } => {
+                assert!(self.queued_mints.remove(&nonce).is_some(), "Mint not queued");
+                env::log_str(&format!("Queued mint cancelled: nonce {}", nonce));
+            }
+            GovernanceAction::CancelTransfer { nonce } => {
+                assert!(!self.processed_vaas.contains(&nonce), "VAA already processed");
+                // Only transfers from Stellar are minted here
+                self.mark_processed(ChainId::Stellar, nonce);
+                env::log_str(&format!("Transfer cancelled for refund: nonce {}", nonce));
+            }
+            GovernanceAction::CancelTimelock { id } => {
+                assert!(
+                    self.scheduled_actions.remove(&id).is_some(),
+                    "Action not scheduled"
+                );
+                env::log_str(&format!("Scheduled action cancelled: id {}", id));
+            }
+            GovernanceAction::Upgrade { code_hash } => {
+                self.approved_upgrade = Some(code_hash);
+                env::log_str(&format!("Upgrade approved: code hash {}", hex::encode(code_hash)));
+            }
+        }
+    }
+
+    /// Schedule governor limits for a wrapped asset (owner only)
+    ///
+    /// Returns the timelock id; run `execute_scheduled` after the delay.
+    pub fn set_token_limit(
+        &mut self,
+        asset_id: String,
+        daily_limit: u128,
+        big_tx_threshold: u128,
+    ) -> u64 {
+        self.assert_owner();
+        assert!(daily_limit > 0 && big_tx_threshold > 0, "Limits must be positive");
+
+        self.schedule(TimelockAction::SetTokenLimit {
+            asset_id,
+            daily_limit,
+            big_tx_threshold,
+        })
+    }
+
+    /// Get governor limits for a wrapped asset (view only)
+    pub fn get_token_limit(&self, asset_id: String) -> Option<TokenLimit> {
+        self.token_limits.get(&asset_id).cloned()
+    }
+
+    /// Get the amount minted in the current 24h window (view only)
+    pub fn get_window_usage(&self, asset_id: String) -> u128 {
+        flows_total(&prune_flows(
+            self.governor_flows.get(&asset_id).cloned().unwrap_or_default(),
+        ))
+    }
+
+    /// Get a mint held back by the governor (view only)
+    pub fn get_queued_mint(&self, nonce: u64) -> Option<QueuedMint> {
+        self.queued_mints.get(&nonce).cloned()
+    }
+
+    /// Get current pause switches (view only)
+    pub fn get_pause_state(&self) -> PauseState {
+        self.paused.clone()
+    }
+
+    /// Record `nonce` as processed, indexed by its origin chain
+    fn mark_processed(&mut self, origin_chain: ChainId, nonce: u64) {
+        self.processed_vaas.insert(nonce);
+        let chain = origin_chain as u8;
+        self.processed_by_origin
+            .entry(chain)
+            .or_insert_with(|| Vector::new(StorageKey::ProcessedFromChain { chain }))
+            .push(nonce);
+    }
+
+    /// Whether a mint of `amount` must be queued instead of executed
+    fn governor_should_queue(&self, asset_id: &String, amount: u128) -> bool {
+        match self.token_limits.get(asset_id) {
+            Some(limit) => {
+                amount >= limit.big_tx_threshold
+                    || self.get_window_usage(asset_id.clone()) + amount > limit.daily_limit
+            }
+            None => false,
+        }
+    }
+
+    /// Mint `amount` of wrapped `asset_id` to `recipient` through its token
+    fn internal_mint(
+        &mut self,
+        nonce: u64,
+        asset_id: &String,
+        recipient: &AccountId,
+        amount: u128,
+    ) {
+        // Update total supply
+        *self.wrapped_supply.entry(asset_id.clone()).or_insert(0) += amount;
+
+        // Record the mint in the governor window
+        if self.token_limits.contains_key(asset_id) {
+            let mut flows =
+                prune_flows(self.governor_flows.get(asset_id).cloned().unwrap_or_default());
+            record_flow(&mut flows, amount);
+            self.governor_flows.insert(asset_id.clone(), flows);
+        }
+
+        self.mint_through_token(nonce, asset_id, recipient, amount);
+    }
+
+    /// Mint through the asset's token and resolve in `on_mint_complete`
+    fn mint_through_token(
+        &mut self,
+        nonce: u64,
+        asset_id: &String,
+        recipient: &AccountId,
+        amount: u128,
+    ) {
+        let token = self.wrapped_token_account(asset_id);
+
+        ext_wrapped_token::ext(token)
+            .with_static_gas(GAS_FOR_MINT)
+            .mint(recipient.clone(), U128(amount))
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_CALLBACK)
+                    .on_mint_complete(nonce, asset_id.clone(), recipient.clone(), U128(amount)),
+            );
+    }
+
+    /// Token contract of a deployed wrapped asset
+    fn wrapped_token_account(&self, asset_id: &String) -> AccountId {
+        let token = self
+            .wrapped_tokens
+            .get(asset_id)
+            .expect("Wrapped token not deployed");
+        assert!(token.deployed, "Wrapped token not deployed");
+        token.account_id.clone()
+    }
+
+    /// Escrow `amount` of `token` and record the lock
+    fn internal_lock(
+        &mut self,
+        token: String,
+        amount: u128,
+        sender: AccountId,
+        recipient_chain: u8,
+        recipient: String,
+    ) -> u64 {
+        assert!(!self.paused.outbound, "Outbound transfers paused");
+        assert!(amount > 0, "Amount must be positive");
+        recipient_address(recipient_chain, &recipient);
+        let initial_storage = env::storage_usage();
+
+        self.lock_nonce += 1;
+        let record = LockRecord {
+            nonce: self.lock_nonce,
+            token,
+            amount,
+            sender,
+            recipient_chain,
+            recipient,
+            timestamp: now_secs(),
+        };
+
+        *self.escrowed.entry(record.token.clone()).or_insert(0) += amount;
+        self.locks.insert(record.nonce, record.clone());
+
+        // Flush so storage usage reflects the new records
+        self.escrowed.flush();
+        self.locks.flush();
+        self.charge_storage(&record.sender, initial_storage);
+
+        emit_lock_event(&record);
+
+        record.nonce
+    }
+
+    /// Charge storage used since `initial_storage` to `account`'s deposit
+    fn charge_storage(&mut self, account: &AccountId, initial_storage: u64) {
+        let storage = self
+            .storage_accounts
+            .get_mut(account)
+            .expect("Storage not registered");
+        storage.used_bytes += env::storage_usage().saturating_sub(initial_storage);
+        assert!(
+            storage.used_cost() <= storage.deposit,
+            "Insufficient storage balance"
+        );
+    }
+
+    /// Measure the bytes one registration uses with the longest account id
+    fn measure_account_storage_usage(&mut self) {
+        let initial_storage = env::storage_usage();
+        let account_id: AccountId = "a".repeat(64).parse().unwrap();
+        let storage = AccountStorage {
+            deposit: NearToken::from_near(0),
+            used_bytes: 0,
+        };
+        self.storage_accounts.insert(account_id.clone(), storage);
+        self.storage_accounts.flush();
+        self.account_storage_usage = env::storage_usage() - initial_storage;
+        self.storage_accounts.remove(&account_id);
+        self.storage_accounts.flush();
+    }
+
+    /// Return part of the attached deposit to the caller
+    fn refund_deposit(amount: NearToken) {
+        if !amount.is_zero() {
+            Promise::new(env::predecessor_account_id()).transfer(amount);
+        }
+    }
+
+    /// Schedule `action` to be executable after the timelock delay
+    fn schedule(&mut self, action: TimelockAction) -> u64 {
+        self.timelock_nonce += 1;
+        let scheduled = ScheduledAction {
+            id: self.timelock_nonce,
+            action,
+            eta: now_secs() + TIMELOCK_DELAY_SECS,
+        };
+        env::log_str(&format!(
+            "Action scheduled: id {} eta {}",
+            scheduled.id, scheduled.eta
+        ));
+        let id = scheduled.id;
+        self.scheduled_actions.insert(id, scheduled);
+
+        id
+    }
+
+    fn assert_owner(&self) {
+        assert_eq!(
+            env::predecessor_account_id(),
+            self.owner,
+            "Only owner can call this method"
+        );
+    }
+
+    fn set_pause_state(&mut self, state: PauseState) {
+        assert!(
+            self.pending_migration.is_none() || (state.inbound && state.outbound),
+            "State migration in progress"
+        );
+        env::log_str(&format!(
+            "Pause state updated: inbound={} outbound={}",
+            state.inbound, state.outbound
+        ));
+        self.paused = state;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::{testing_env, VMContext};
+
+    fn get_context(predecessor: AccountId) -> VMContext {
+        VMContextBuilder::new()
+            .predecessor_account_id(predecessor)
+            .build()
+    }
+
+    #[test]
+    fn test_initialization() {
+        let context = get_context("owner.near".parse().unwrap());
+        testing_env!(context);
+
+        let guardians = vec![[1u8; 32], [2u8; 32]];
+        let contract = NearBridge::new("owner.near".parse().unwrap(), guardians, 2);
+
+        assert_eq!(contract.guardians.len(), 2);
+        assert_eq!(contract.quorum, 2);
+    }
+
+    #[test]
+    fn test_balance_of() {
+        let context = get_context("owner.near".parse().unwrap());
+        testing_env!(context);
+
+        let contract = NearBridge::new(
+            "owner.near".parse().unwrap(),
+            vec![[1u8; 32]],
+            1,
+        );
+
+        let balance = contract.balance_of(
+            "test_asset".to_string(),
+            "user.near".parse().unwrap(),
+        );
+        assert_eq!(balance, 0);
+    }
+
+    fn sign_governance(vaa: &mut GovernanceVAA, seeds: &[u8]) {
+        use ed25519_dalek::{Signer, SigningKey};
+
+        for seed in seeds {
+            let key = SigningKey::from_bytes(&[*seed; 32]);
+            let signature = key.sign(&vaa.digest());
+            vaa.add_signature(key.verifying_key().to_bytes(), signature.to_bytes());
+        }
+    }
+
+    fn guardian_pubkeys(seeds: &[u8]) -> Vec<[u8; 32]> {
+        seeds
+            .iter()
+            .map(|seed| {
+                ed25519_dalek::SigningKey::from_bytes(&[*seed; 32])
+                    .verifying_key()
+                    .to_bytes()
+            })
+            .collect()
+    }
+
+    const ASSET: [u8; 32] = [7u8; 32];
+    const IMPLICIT_RECIPIENT: [u8; 32] = [5u8; 32];
+
+    fn asset_id() -> String {
+        hex::encode(ASSET)
+    }
+
+    /// Stellar -> NEAR transfer VAA for this contract, signed by guardian `seeds`
+    fn transfer_vaa(nonce: u64, amount: u128, recipient: [u8; 32], seeds: &[u8]) -> VAA {
+        let mut vaa = VAA::new(
+            ChainId::Stellar,
+            [0u8; 32],
+            ChainId::Near,
+            contract_address(env::current_account_id().as_str()),
+            ASSET,
+            amount,
+            recipient,
+            nonce,
+            0,
+        );
+        sign_vaa(&mut vaa, seeds);
+        vaa
+    }
+
+    fn sign_vaa(vaa: &mut VAA, seeds: &[u8]) {
+        use ed25519_dalek::{Signer, SigningKey};
+
+        for seed in seeds {
+            let key = SigningKey::from_bytes(&[*seed; 32]);
+            let signature = key.sign(&vaa.digest());
+            vaa.add_signature(key.verifying_key().to_bytes(), signature.to_bytes());
+        }
+    }
+
+    fn vaa_json(vaa: &VAA) -> String {
+        String::from_utf8(vaa.to_bytes().unwrap()).unwrap()
+    }
+
+    fn usdc_metadata() -> WrappedTokenMetadata {
+        WrappedTokenMetadata {
+            name: "USD Coin (Stellar)".to_string(),
+            symbol: "USDC".to_string(),
+            icon: None,
+            decimals: 7,
+        }
+    }
+
+    /// Deploy the test asset's token as the owner and confirm the deployment
+    fn deploy_token(contract: &mut NearBridge) {
+        let mut context = VMContextBuilder::new()
+            .predecessor_account_id("owner.near".parse().unwrap())
+            .attached_deposit(TOKEN_DEPLOY_DEPOSIT)
+            .build();
+        context.input = b"wasm".to_vec();
+        testing_env!(context);
+        contract.set_token_code();
+        contract.deploy_wrapped_token(asset_id(), usdc_metadata());
+        contract.on_token_deployed(asset_id(), TOKEN_DEPLOY_DEPOSIT, Ok(()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Outbound transfers paused")]
+    fn test_pauser_blocks_burn() {
+        let context = get_context("owner.near".parse().unwrap());
+        testing_env!(context);
+
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), vec![[1u8; 32]], 1);
+        contract.set_pauser(Some("pauser.near".parse().unwrap()));
+
+        testing_env!(get_context("pauser.near".parse().unwrap()));
+        contract.pause(false, true);
+        assert_eq!(
+            contract.get_pause_state(),
+            PauseState {
+                inbound: false,
+                outbound: true
+            }
+        );
+
+        contract.burn_wrapped("test_asset".to_string(), 1, 1, "G...".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "Only owner can call this method")]
+    fn test_pauser_cannot_unpause() {
+        let context = get_context("owner.near".parse().unwrap());
+        testing_env!(context);
+
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), vec![[1u8; 32]], 1);
+        contract.set_pauser(Some("pauser.near".parse().unwrap()));
+
+        testing_env!(get_context("pauser.near".parse().unwrap()));
+        contract.pause(true, true);
+        contract.unpause(true, true);
+    }
+
+    #[test]
+    fn test_governance_unpause() {
+        let context = get_context("owner.near".parse().unwrap());
+        testing_env!(context);
+
+        let mut contract = NearBridge::new(
+            "owner.near".parse().unwrap(),
+            guardian_pubkeys(&[1, 2, 3]),
+            2,
+        );
+        contract.pause(true, true);
+
+        let mut vaa = GovernanceVAA::new(
+            ChainId::Near,
+            contract_address(env::current_account_id().as_str()),
+            1,
+            GovernanceAction::SetPause {
+                inbound: false,
+                outbound: false,
+            },
+        );
+        sign_governance(&mut vaa, &[1, 3]);
+        contract.submit_governance(String::from_utf8(vaa.to_bytes().unwrap()).unwrap());
+
+        assert_eq!(contract.get_pause_state(), PauseState::default());
+    }
+
+    #[test]
+    #[should_panic(expected = "Insufficient guardian signatures")]
+    fn test_governance_below_quorum() {
+        let context = get_context("owner.near".parse().unwrap());
+        testing_env!(context);
+
+        let mut contract = NearBridge::new(
+            "owner.near".parse().unwrap(),
+            guardian_pubkeys(&[1, 2, 3]),
+            2,
+        );
+
+        let mut vaa = GovernanceVAA::new(
+            ChainId::Near,
+            contract_address(env::current_account_id().as_str()),
+            1,
+            GovernanceAction::SetPause {
+                inbound: true,
+                outbound: true,
+            },
+        );
+        sign_governance(&mut vaa, &[1, 9]);
+        contract.submit_governance(String::from_utf8(vaa.to_bytes().unwrap()).unwrap());
+    }
+
+    #[test]
+    fn test_big_mint_is_queued() {
+        let context = get_context("owner.near".parse().unwrap());
+        testing_env!(context);
+
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), guardian_pubkeys(&[1]), 1);
+        deploy_token(&mut contract);
+        set_token_limit_now(&mut contract, 5000, 500);
+
+        contract.mint_wrapped(vaa_json(&transfer_vaa(1, 1000, IMPLICIT_RECIPIENT, &[1])), None);
+        assert_eq!(contract.total_supply(asset_id()), 0);
+
+        let queued = contract.get_queued_mint(1).unwrap();
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id("anyone.near".parse().unwrap())
+            .block_timestamp(queued.release_after * 1_000_000_000)
+            .build());
+        contract.execute_queued_mint(1);
+
+        assert_eq!(contract.total_supply(asset_id()), 1000);
+        assert!(contract.get_queued_mint(1).is_none());
+        assert_eq!(contract.get_window_usage(asset_id()), 1000);
+    }
+
+    #[test]
+    fn test_mints_share_hourly_flow() {
+        testing_env!(get_context("owner.near".parse().unwrap()));
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), guardian_pubkeys(&[1]), 1);
+        deploy_token(&mut contract);
+        set_token_limit_now(&mut contract, 5000, 500);
+
+        contract.mint_wrapped(vaa_json(&transfer_vaa(1, 100, IMPLICIT_RECIPIENT, &[1])), None);
+        contract.mint_wrapped(vaa_json(&transfer_vaa(2, 100, IMPLICIT_RECIPIENT, &[1])), None);
+        assert_eq!(contract.governor_flows.get(&asset_id()).unwrap().len(), 1);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id("owner.near".parse().unwrap())
+            .block_timestamp((TIMELOCK_DELAY_SECS + governor::FLOW_BUCKET_SECS) * 1_000_000_000)
+            .build());
+        contract.mint_wrapped(vaa_json(&transfer_vaa(3, 100, IMPLICIT_RECIPIENT, &[1])), None);
+        assert_eq!(contract.governor_flows.get(&asset_id()).unwrap().len(), 2);
+        assert_eq!(contract.get_window_usage(asset_id()), 300);
+    }
+
+    #[test]
+    #[should_panic(expected = "Mint still delayed")]
+    fn test_queued_mint_before_delay() {
+        let context = get_context("owner.near".parse().unwrap());
+        testing_env!(context);
+
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), guardian_pubkeys(&[1]), 1);
+        set_token_limit_now(&mut contract, 500, 5000);
+
+        // Over the daily limit, so queued
+        contract.mint_wrapped(vaa_json(&transfer_vaa(1, 1000, IMPLICIT_RECIPIENT, &[1])), None);
+        contract.execute_queued_mint(1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Mint not queued")]
+    fn test_governance_cancels_queued_mint() {
+        let context = get_context("owner.near".parse().unwrap());
+        testing_env!(context);
+
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), guardian_pubkeys(&[1, 2]), 2);
+        set_token_limit_now(&mut contract, 5000, 500);
+        contract.mint_wrapped(vaa_json(&transfer_vaa(1, 1000, IMPLICIT_RECIPIENT, &[1, 2])), None);
+
+        let mut vaa = GovernanceVAA::new(
+            ChainId::Near,
+            contract_address(env::current_account_id().as_str()),
+            1,
+            GovernanceAction::CancelQueuedTransfer { nonce: 1 },
+        );
+        sign_governance(&mut vaa, &[1, 2]);
+        contract.submit_governance(String::from_utf8(vaa.to_bytes().unwrap()).unwrap());
+
+        contract.execute_queued_mint(1);
+    }
+
+    #[test]
+    #[should_panic(expected = "VAA already processed")]
+    fn test_cancelled_transfer_cannot_be_minted() {
+        let context = get_context("owner.near".parse().unwrap());
+        testing_env!(context);
+
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), guardian_pubkeys(&[1]), 1);
+
+        let mut vaa = GovernanceVAA::new(
+            ChainId::Near,
+            contract_address(env::current_account_id().as_str()),
+            1,
+            GovernanceAction::CancelTransfer { nonce: 1 },
+        );
+        sign_governance(&mut vaa, &[1]);
+        contract.submit_governance(String::from_utf8(vaa.to_bytes().unwrap()).unwrap());
+        assert!(contract.is_vaa_processed(1));
+
+        contract.mint_wrapped(vaa_json(&transfer_vaa(1, 1000, IMPLICIT_RECIPIENT, &[1])), None);
+    }
+
+    /// Schedule limits for the test asset and let the timelock pass
+    fn set_token_limit_now(contract: &mut NearBridge, daily_limit: u128, threshold: u128) {
+        let id = contract.set_token_limit(asset_id(), daily_limit, threshold);
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id("owner.near".parse().unwrap())
+            .block_timestamp(TIMELOCK_DELAY_SECS * 1_000_000_000)
+            .build());
+        contract.execute_scheduled(id);
+    }
+
+    #[test]
+    fn test_mint_wrapped_to_implicit_account() {
+        testing_env!(get_context("relayer.near".parse().unwrap()));
+        let mut contract =
+            NearBridge::new("owner.near".parse().unwrap(), guardian_pubkeys(&[1, 2, 3]), 2);
+        deploy_token(&mut contract);
+
+        testing_env!(get_context("relayer.near".parse().unwrap()));
+        contract.mint_wrapped(vaa_json(&transfer_vaa(1, 1000, IMPLICIT_RECIPIENT, &[1, 3])), None);
+        assert_eq!(contract.total_supply(asset_id()), 1000);
+        assert!(contract.is_vaa_processed(1));
+
+        let recipient: AccountId = hex::encode(IMPLICIT_RECIPIENT).parse().unwrap();
+        assert!(contract.on_mint_complete(1, asset_id(), recipient, U128(1000), Ok(())));
+        assert_eq!(
+            near_sdk::test_utils::get_logs().last().unwrap(),
+            &format!("Minted 1000 of {} to {}", asset_id(), hex::encode(IMPLICIT_RECIPIENT))
+        );
+    }
+
+    #[test]
+    fn test_mint_wrapped_to_named_account() {
+        testing_env!(get_context("relayer.near".parse().unwrap()));
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), guardian_pubkeys(&[1]), 1);
+        deploy_token(&mut contract);
+
+        testing_env!(get_context("relayer.near".parse().unwrap()));
+        let bob: AccountId = "bob.near".parse().unwrap();
+        let vaa = transfer_vaa(1, 250, contract_address(bob.as_str()), &[1]);
+        contract.mint_wrapped(vaa_json(&vaa), Some(bob));
+
+        assert_eq!(contract.total_supply(asset_id()), 250);
+    }
+
+    #[test]
+    #[should_panic(expected = "Recipient does not match VAA")]
+    fn test_mint_wrapped_wrong_named_recipient() {
+        testing_env!(get_context("relayer.near".parse().unwrap()));
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), guardian_pubkeys(&[1]), 1);
+
+        let vaa = transfer_vaa(1, 250, contract_address("bob.near"), &[1]);
+        contract.mint_wrapped(vaa_json(&vaa), Some("mallory.near".parse().unwrap()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Insufficient guardian signatures")]
+    fn test_mint_wrapped_below_quorum() {
+        testing_env!(get_context("relayer.near".parse().unwrap()));
+        let mut contract =
+            NearBridge::new("owner.near".parse().unwrap(), guardian_pubkeys(&[1, 2, 3]), 2);
+
+        // One guardian plus a key outside the guardian set
+        let vaa = transfer_vaa(1, 1000, IMPLICIT_RECIPIENT, &[1, 9]);
+        contract.mint_wrapped(vaa_json(&vaa), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Insufficient guardian signatures")]
+    fn test_mint_wrapped_tampered_amount() {
+        testing_env!(get_context("relayer.near".parse().unwrap()));
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), guardian_pubkeys(&[1]), 1);
+
+        let mut vaa = transfer_vaa(1, 1000, IMPLICIT_RECIPIENT, &[1]);
+        vaa.amount = 1_000_000;
+        contract.mint_wrapped(vaa_json(&vaa), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Wrong destination contract")]
+    fn test_mint_wrapped_wrong_destination() {
+        testing_env!(get_context("relayer.near".parse().unwrap()));
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), guardian_pubkeys(&[1]), 1);
+
+        let mut vaa = transfer_vaa(1, 1000, IMPLICIT_RECIPIENT, &[]);
+        vaa.destination_contract = contract_address("other-bridge.near");
+        sign_vaa(&mut vaa, &[1]);
+        contract.mint_wrapped(vaa_json(&vaa), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "VAA already processed")]
+    fn test_mint_wrapped_replay() {
+        testing_env!(get_context("relayer.near".parse().unwrap()));
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), guardian_pubkeys(&[1]), 1);
+        deploy_token(&mut contract);
+
+        let vaa = vaa_json(&transfer_vaa(1, 1000, IMPLICIT_RECIPIENT, &[1]));
+        contract.mint_wrapped(vaa.clone(), None);
+        contract.mint_wrapped(vaa, None);
+    }
+
+    #[test]
+    fn test_deploy_wrapped_token() {
+        testing_env!(get_context("owner.near".parse().unwrap()));
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), vec![[1u8; 32]], 1);
+        deploy_token(&mut contract);
+
+        let token = contract.get_wrapped_token(asset_id()).unwrap();
+        assert_eq!(token.account_id.as_str(), "usdc-stellar.alice.near");
+        assert_eq!(token.metadata, usdc_metadata());
+        assert!(token.deployed);
+    }
+
+    #[test]
+    fn test_failed_token_deployment_is_forgotten() {
+        let mut context = VMContextBuilder::new()
+            .predecessor_account_id("owner.near".parse().unwrap())
+            .attached_deposit(TOKEN_DEPLOY_DEPOSIT)
+            .build();
+        context.input = b"wasm".to_vec();
+        testing_env!(context);
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), vec![[1u8; 32]], 1);
+        contract.set_token_code();
+        contract.deploy_wrapped_token(asset_id(), usdc_metadata());
+        assert!(!contract.get_wrapped_token(asset_id()).unwrap().deployed);
+
+        let result = Err(PromiseError::Failed);
+        assert!(!contract.on_token_deployed(asset_id(), TOKEN_DEPLOY_DEPOSIT, result));
+        assert!(contract.get_wrapped_token(asset_id()).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Wrapped token not deployed")]
+    fn test_mint_requires_deployed_token() {
+        testing_env!(get_context("relayer.near".parse().unwrap()));
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), guardian_pubkeys(&[1]), 1);
+
+        contract.mint_wrapped(vaa_json(&transfer_vaa(1, 1000, IMPLICIT_RECIPIENT, &[1])), None);
+    }
+
+    #[test]
+    fn test_failed_mint_is_held_for_claim() {
+        testing_env!(get_context("relayer.near".parse().unwrap()));
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), guardian_pubkeys(&[1]), 1);
+        deploy_token(&mut contract);
+
+        contract.mint_wrapped(vaa_json(&transfer_vaa(1, 1000, IMPLICIT_RECIPIENT, &[1])), None);
+        let recipient: AccountId = hex::encode(IMPLICIT_RECIPIENT).parse().unwrap();
+        let result = Err(PromiseError::Failed);
+        assert!(!contract.on_mint_complete(1, asset_id(), recipient.clone(), U128(1000), result));
+
+        assert_eq!(contract.total_supply(asset_id()), 0);
+        assert!(contract.get_queued_mint(1).is_none());
+        let claim = contract.get_pending_claim(1).unwrap();
+        assert_eq!(claim.recipient, recipient);
+        assert_eq!(claim.amount, 1000);
+
+        contract.claim_wrapped(1);
+        assert_eq!(contract.total_supply(asset_id()), 1000);
+        assert!(contract.get_pending_claim(1).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Nothing to claim")]
+    fn test_claim_wrapped_without_failed_mint() {
+        testing_env!(get_context("relayer.near".parse().unwrap()));
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), guardian_pubkeys(&[1]), 1);
+        deploy_token(&mut contract);
+
+        contract.mint_wrapped(vaa_json(&transfer_vaa(1, 1000, IMPLICIT_RECIPIENT, &[1])), None);
+        contract.claim_wrapped(1);
+    }
+
+    const STELLAR_RECIPIENT: &str = "GBRPYHIL2CI3FNQ4BXLFMNDLFJUNPU2HY3ZMFSHONUCEOASW7QC7OX2H";
+
+    #[test]
+    fn test_burn_wrapped_reduces_supply_on_success() {
+        testing_env!(get_context("relayer.near".parse().unwrap()));
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), guardian_pubkeys(&[1]), 1);
+        deploy_token(&mut contract);
+        contract.mint_wrapped(vaa_json(&transfer_vaa(1, 1000, IMPLICIT_RECIPIENT, &[1])), None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id("bob.near".parse().unwrap())
+            .attached_deposit(NearToken::from_yoctonear(1))
+            .build());
+        contract.burn_wrapped(asset_id(), 400, 1, STELLAR_RECIPIENT.to_string());
+        assert_eq!(contract.total_supply(asset_id()), 1000);
+
+        let bob: AccountId = "bob.near".parse().unwrap();
+        let recipient = STELLAR_RECIPIENT.to_string();
+        let failed = Err(PromiseError::Failed);
+        let burned = contract.on_burn_complete(
+            asset_id(),
+            bob.clone(),
+            U128(400),
+            1,
+            recipient.clone(),
+            failed,
+        );
+        assert!(!burned);
+        assert_eq!(contract.total_supply(asset_id()), 1000);
+
+        assert!(contract.on_burn_complete(asset_id(), bob, U128(400), 1, recipient, Ok(())));
+        assert_eq!(contract.total_supply(asset_id()), 600);
+    }
+
+    #[test]
+    fn test_burn_records_outbound_message() {
+        testing_env!(get_context("relayer.near".parse().unwrap()));
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), guardian_pubkeys(&[1]), 1);
+        deploy_token(&mut contract);
+        contract.mint_wrapped(vaa_json(&transfer_vaa(1, 1000, IMPLICIT_RECIPIENT, &[1])), None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id("relayer.near".parse().unwrap())
+            .block_height(42)
+            .build());
+        let bob: AccountId = "bob.near".parse().unwrap();
+        let recipient = STELLAR_RECIPIENT.to_string();
+        for _ in 0..2 {
+            let (sender, recipient) = (bob.clone(), recipient.clone());
+            contract.on_burn_complete(asset_id(), sender, U128(100), 1, recipient, Ok(()));
+        }
+
+        let message = contract.get_outbound_message(2).unwrap();
+        assert_eq!(message.sequence, 2);
+        assert_eq!(message.sender, bob);
+        assert_eq!(message.amount, 100);
+        assert_eq!(message.block_height, 42);
+        assert_eq!(
+            message.recipient_address,
+            hex::encode(parse_address(ChainId::Stellar, STELLAR_RECIPIENT).unwrap())
+        );
+        assert!(contract.get_outbound_message(3).is_none());
+        assert!(near_sdk::test_utils::get_logs()
+            .last()
+            .unwrap()
+            .contains(r#""event":"burn""#));
+    }
+
+    #[test]
+    fn test_paginated_views() {
+        testing_env!(get_context("relayer.near".parse().unwrap()));
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), guardian_pubkeys(&[1]), 1);
+        deploy_token(&mut contract);
+        for nonce in 1..=3 {
+            let vaa = transfer_vaa(nonce, 100, IMPLICIT_RECIPIENT, &[1]);
+            contract.mint_wrapped(vaa_json(&vaa), None);
+        }
+
+        let stellar = ChainId::Stellar as u8;
+        assert_eq!(contract.list_processed(stellar, 0, 10), vec![1, 2, 3]);
+        assert_eq!(contract.list_processed(stellar, 1, 1), vec![2]);
+        assert!(contract.list_processed(stellar, 5, 10).is_empty());
+        assert!(contract.list_processed(ChainId::Near as u8, 0, 10).is_empty());
+
+        let assets = contract.list_assets(0, 10);
+        assert_eq!(assets.len(), 1);
+        assert_eq!(assets[0].token.asset_id, asset_id());
+        assert_eq!(assets[0].total_supply, 300);
+
+        for sender in ["bob.near", "carol.near", "bob.near"] {
+            let (sender, recipient) = (sender.parse().unwrap(), STELLAR_RECIPIENT.to_string());
+            contract.on_burn_complete(asset_id(), sender, U128(50), 1, recipient, Ok(()));
+        }
+        let bob_burns = contract.list_outbound_by_sender("bob.near".parse().unwrap(), 0, 10);
+        let sequences: Vec<u64> = bob_burns.iter().map(|message| message.sequence).collect();
+        assert_eq!(sequences, vec![1, 3]);
+        assert!(contract
+            .list_outbound_by_sender("dave.near".parse().unwrap(), 0, 10)
+            .is_empty());
+
+        let sequences = |from, limit| -> Vec<u64> {
+            let messages = contract.list_outbound(from, limit);
+            messages.iter().map(|message| message.sequence).collect()
+        };
+        assert_eq!(sequences(0, 10), vec![1, 2, 3]);
+        assert_eq!(sequences(2, 1), vec![2]);
+        assert!(sequences(4, 10).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid recipient: invalid Stellar address")]
+    fn test_burn_wrapped_rejects_mistyped_recipient() {
+        testing_env!(get_context("bob.near".parse().unwrap()));
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), guardian_pubkeys(&[1]), 1);
+        deploy_token(&mut contract);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id("bob.near".parse().unwrap())
+            .attached_deposit(NearToken::from_yoctonear(1))
+            .build());
+        let typo = STELLAR_RECIPIENT.replacen('Y', "Z", 1);
+        contract.burn_wrapped(asset_id(), 400, 1, typo);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid recipient chain: unknown chain id 9")]
+    fn test_burn_wrapped_rejects_unknown_chain() {
+        testing_env!(get_context("bob.near".parse().unwrap()));
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), guardian_pubkeys(&[1]), 1);
+        deploy_token(&mut contract);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id("bob.near".parse().unwrap())
+            .attached_deposit(NearToken::from_yoctonear(1))
+            .build());
+        contract.burn_wrapped(asset_id(), 400, 9, STELLAR_RECIPIENT.to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires attached deposit of exactly 1 yoctoNEAR")]
+    fn test_burn_wrapped_requires_one_yocto() {
+        testing_env!(get_context("bob.near".parse().unwrap()));
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), guardian_pubkeys(&[1]), 1);
+        deploy_token(&mut contract);
+
+        testing_env!(get_context("bob.near".parse().unwrap()));
+        contract.burn_wrapped(asset_id(), 400, 1, STELLAR_RECIPIENT.to_string());
+    }
+
+    #[test]
+    fn test_guardian_update_is_timelocked() {
+        testing_env!(get_context("owner.near".parse().unwrap()));
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), vec![[1u8; 32]], 1);
+
+        let id = contract.update_guardians(vec![[2u8; 32], [3u8; 32]], 2);
+        assert_eq!(contract.get_guardians(), vec![[1u8; 32]]);
+        assert_eq!(contract.get_scheduled(id).unwrap().eta, TIMELOCK_DELAY_SECS);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id("anyone.near".parse().unwrap())
+            .block_timestamp(TIMELOCK_DELAY_SECS * 1_000_000_000)
+            .build());
+        contract.execute_scheduled(id);
+        assert_eq!(contract.get_guardians(), vec![[2u8; 32], [3u8; 32]]);
+        assert_eq!(contract.quorum, 2);
+
+        let config = contract.get_config();
+        assert_eq!(config.guardian_set_index, 1);
+        assert_eq!(config.quorum, 2);
+        assert_eq!(config.paused, PauseState::default());
+        assert_eq!(config.storage_deposit_min, contract.storage_balance_bounds().min);
+    }
+
+    #[test]
+    #[should_panic(expected = "Action still timelocked")]
+    fn test_scheduled_action_before_delay() {
+        testing_env!(get_context("owner.near".parse().unwrap()));
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), vec![[1u8; 32]], 1);
+
+        let id = contract.update_guardians(vec![[2u8; 32]], 1);
+        contract.execute_scheduled(id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Action not scheduled")]
+    fn test_governance_cancels_scheduled_action() {
+        testing_env!(get_context("owner.near".parse().unwrap()));
+        let mut contract =
+            NearBridge::new("owner.near".parse().unwrap(), guardian_pubkeys(&[1]), 1);
+
+        let id = contract.update_guardians(vec![[2u8; 32]], 1);
+
+        let mut vaa = GovernanceVAA::new(
+            ChainId::Near,
+            contract_address(env::current_account_id().as_str()),
+            1,
+            GovernanceAction::CancelTimelock { id },
+        );
+        sign_governance(&mut vaa, &[1]);
+        contract.submit_governance(String::from_utf8(vaa.to_bytes().unwrap()).unwrap());
+        assert!(contract.get_scheduled(id).is_none());
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id("anyone.near".parse().unwrap())
+            .block_timestamp(TIMELOCK_DELAY_SECS * 1_000_000_000)
+            .build());
+        contract.execute_scheduled(id);
+    }
+
+    const ONE_NEAR: NearToken = NearToken::from_near(1);
+
+    /// Accept locks of `token.near`
+    fn accept_lock_token(contract: &mut NearBridge) {
+        testing_env!(get_context("owner.near".parse().unwrap()));
+        contract.set_lock_token("token.near".parse().unwrap(), true);
+    }
+
+    /// Register `account` for storage with a 1 NEAR deposit
+    fn register_storage(contract: &mut NearBridge, account: &str) {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(account.parse().unwrap())
+            .attached_deposit(ONE_NEAR)
+            .build());
+        contract.storage_deposit(None, None);
+    }
+
+    #[test]
+    fn test_storage_deposit_registration_only() {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id("alice.near".parse().unwrap())
+            .attached_deposit(ONE_NEAR)
+            .build());
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), vec![[1u8; 32]], 1);
+        let min = contract.storage_balance_bounds().min;
+        assert!(!min.is_zero() && min < ONE_NEAR);
+
+        let balance = contract.storage_deposit(None, Some(true));
+        assert_eq!(
+            balance,
+            StorageBalance {
+                total: min,
+                available: NearToken::from_near(0)
+            }
+        );
+        assert_eq!(contract.storage_balance_of("alice.near".parse().unwrap()), Some(balance));
+        assert!(contract.storage_balance_of("bob.near".parse().unwrap()).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Insufficient storage deposit")]
+    fn test_storage_deposit_below_minimum() {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id("alice.near".parse().unwrap())
+            .attached_deposit(NearToken::from_yoctonear(1))
+            .build());
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), vec![[1u8; 32]], 1);
+        contract.storage_deposit(None, None);
+    }
+
+    #[test]
+    fn test_storage_withdraw_and_unregister() {
+        testing_env!(get_context("owner.near".parse().unwrap()));
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), vec![[1u8; 32]], 1);
+        register_storage(&mut contract, "alice.near");
+        let min = contract.storage_balance_bounds().min;
+        let withdrawn = NearToken::from_yoctonear(1_000);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id("alice.near".parse().unwrap())
+            .attached_deposit(NearToken::from_yoctonear(1))
+            .build());
+        let balance = contract.storage_withdraw(Some(withdrawn));
+        assert_eq!(balance.total, ONE_NEAR.saturating_sub(withdrawn));
+        assert_eq!(balance.available, balance.total.saturating_sub(min));
+
+        assert!(contract.storage_unregister(None));
+        assert!(contract.storage_balance_of("alice.near".parse().unwrap()).is_none());
+        assert!(!contract.storage_unregister(None));
+    }
+
+    #[test]
+    fn test_lock_charges_sender_storage() {
+        testing_env!(get_context("owner.near".parse().unwrap()));
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), vec![[1u8; 32]], 1);
+        register_storage(&mut contract, "alice.near");
+        accept_lock_token(&mut contract);
+        let before = contract.storage_balance_of("alice.near".parse().unwrap()).unwrap();
+
+        testing_env!(get_context("token.near".parse().unwrap()));
+        contract.ft_on_transfer("alice.near".parse().unwrap(), U128(500), lock_message());
+
+        let after = contract.storage_balance_of("alice.near".parse().unwrap()).unwrap();
+        assert_eq!(after.total, before.total);
+        assert!(after.available < before.available);
+    }
+
+    #[test]
+    #[should_panic(expected = "Storage not registered")]
+    fn test_lock_requires_storage_registration() {
+        testing_env!(get_context("owner.near".parse().unwrap()));
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), vec![[1u8; 32]], 1);
+        accept_lock_token(&mut contract);
+
+        testing_env!(get_context("token.near".parse().unwrap()));
+        contract.ft_on_transfer("alice.near".parse().unwrap(), U128(500), lock_message());
+    }
+
+    #[test]
+    #[should_panic(expected = "Storage still in use")]
+    fn test_storage_unregister_with_locks() {
+        testing_env!(get_context("owner.near".parse().unwrap()));
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), vec![[1u8; 32]], 1);
+        register_storage(&mut contract, "alice.near");
+        accept_lock_token(&mut contract);
+
+        testing_env!(get_context("token.near".parse().unwrap()));
+        contract.ft_on_transfer("alice.near".parse().unwrap(), U128(500), lock_message());
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id("alice.near".parse().unwrap())
+            .attached_deposit(NearToken::from_yoctonear(1))
+            .build());
+        contract.storage_unregister(None);
+    }
+
+    fn lock_message() -> String {
+        format!(
+            r#"{{"recipient_chain": 1, "recipient": "{}"}}"#,
+            STELLAR_RECIPIENT
+        )
+    }
+
+    #[test]
+    fn test_ft_on_transfer_locks_tokens() {
+        testing_env!(get_context("token.near".parse().unwrap()));
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), vec![[1u8; 32]], 1);
+        register_storage(&mut contract, "alice.near");
+        accept_lock_token(&mut contract);
+
+        testing_env!(get_context("token.near".parse().unwrap()));
+
+        let refund = contract.ft_on_transfer(
+            "alice.near".parse().unwrap(),
+            U128(500),
+            lock_message(),
+        );
+        assert!(matches!(refund, PromiseOrValue::Value(U128(0))));
+
+        let record = contract.get_lock(1).unwrap();
+        assert_eq!(record.token, "token.near");
+        assert_eq!(record.sender, "alice.near".parse::<AccountId>().unwrap());
+        assert_eq!(record.amount, 500);
+        assert_eq!(record.recipient, STELLAR_RECIPIENT);
+        assert_eq!(contract.get_escrowed_balance("token.near".to_string()), 500);
+        assert!(near_sdk::test_utils::get_logs()[0].starts_with("EVENT_JSON:"));
+    }
+
+    #[test]
+    fn test_lock_near() {
+        testing_env!(get_context("owner.near".parse().unwrap()));
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), vec![[1u8; 32]], 1);
+        register_storage(&mut contract, "alice.near");
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id("alice.near".parse().unwrap())
+            .attached_deposit(NearToken::from_yoctonear(1_000))
+            .build());
+
+        assert_eq!(contract.lock_near(1, STELLAR_RECIPIENT.to_string()), 1);
+        assert_eq!(contract.lock_near(1, STELLAR_RECIPIENT.to_string()), 2);
+        assert_eq!(contract.get_escrowed_balance(NATIVE_TOKEN_ID.to_string()), 2_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid recipient")]
+    fn test_lock_near_rejects_invalid_recipient() {
+        testing_env!(get_context("owner.near".parse().unwrap()));
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), vec![[1u8; 32]], 1);
+        register_storage(&mut contract, "alice.near");
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id("alice.near".parse().unwrap())
+            .attached_deposit(NearToken::from_yoctonear(1_000))
+            .build());
+        contract.lock_near(1, "GABC".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "Recipient chain must be a remote chain")]
+    fn test_ft_on_transfer_rejects_near_recipient_chain() {
+        testing_env!(get_context("owner.near".parse().unwrap()));
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), vec![[1u8; 32]], 1);
+        register_storage(&mut contract, "alice.near");
+        accept_lock_token(&mut contract);
+
+        testing_env!(get_context("token.near".parse().unwrap()));
+        let msg = format!(r#"{{"recipient_chain": 2, "recipient": "{}"}}"#, STELLAR_RECIPIENT);
+        contract.ft_on_transfer("alice.near".parse().unwrap(), U128(500), msg);
+    }
+
+    #[test]
+    #[should_panic(expected = "Token not accepted for locking")]
+    fn test_ft_on_transfer_rejects_unaccepted_token() {
+        testing_env!(get_context("owner.near".parse().unwrap()));
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), vec![[1u8; 32]], 1);
+        register_storage(&mut contract, "alice.near");
+        accept_lock_token(&mut contract);
+        contract.set_lock_token("token.near".parse().unwrap(), false);
+        assert!(!contract.is_lock_token("token.near".parse().unwrap()));
+
+        testing_env!(get_context("fake.near".parse().unwrap()));
+        contract.ft_on_transfer("alice.near".parse().unwrap(), U128(500), lock_message());
+    }
+
+    #[test]
+    #[should_panic(expected = "Outbound transfers paused")]
+    fn test_paused_ft_on_transfer_refunds() {
+        testing_env!(get_context("owner.near".parse().unwrap()));
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), vec![[1u8; 32]], 1);
+        accept_lock_token(&mut contract);
+        contract.pause(false, true);
+
+        testing_env!(get_context("token.near".parse().unwrap()));
+        contract.ft_on_transfer("alice.near".parse().unwrap(), U128(500), lock_message());
+    }
+
+    /// Version 1 state as written by the first release: `nonces` processed
+    /// VAAs and `holders` legacy balances of one asset
+    fn write_v1_state(nonces: u64, holders: u32) {
+        use near_sdk::collections::{UnorderedMap, UnorderedSet};
+
+        let mut state = NearBridgeV1 {
+            owner: "owner.near".parse().unwrap(),
+            guardians: vec![Guardian { pubkey: [1u8; 32] }],
+            quorum: 1,
+            processed_vaas: UnorderedSet::new(b"p".to_vec()),
+            wrapped_balances: UnorderedMap::new(b"b".to_vec()),
+            wrapped_supply: UnorderedMap::new(b"s".to_vec()),
+        };
+        for nonce in 1..=nonces {
+            state.processed_vaas.insert(&nonce);
+        }
+        let mut balances = UnorderedMap::new(format!("b:{}", asset_id()).into_bytes());
+        for holder in 0..holders {
+            let account: AccountId = format!("holder{}.near", holder).parse().unwrap();
+            balances.insert(&account, &10);
+        }
+        state.wrapped_balances.insert(&asset_id(), &balances);
+        state
+            .wrapped_supply
+            .insert(&asset_id(), &(u128::from(holders) * 10));
+        env::state_write(&state);
+    }
+
+    #[test]
+    fn test_migrate_from_v1() {
+        testing_env!(get_context(env::current_account_id()));
+        write_v1_state(1, 1);
+
+        let mut contract = NearBridge::migrate();
+        assert_eq!(contract.owner, "owner.near".parse::<AccountId>().unwrap());
+        assert_eq!(contract.quorum, 1);
+        assert!(contract.account_storage_usage > 0);
+        assert_eq!(
+            contract.paused,
+            PauseState {
+                inbound: true,
+                outbound: true
+            }
+        );
+
+        assert!(contract.migrate_batch());
+        assert!(contract.pending_migration.is_none());
+        assert!(contract.paused.inbound && contract.paused.outbound);
+        testing_env!(get_context("owner.near".parse().unwrap()));
+        contract.unpause(true, true);
+        assert_eq!(contract.paused, PauseState::default());
+        assert!(contract.is_vaa_processed(1));
+        assert_eq!(contract.list_processed(ChainId::Stellar as u8, 0, 10), vec![1]);
+        assert_eq!(contract.total_supply(asset_id()), 10);
+        let holder: AccountId = "holder0.near".parse().unwrap();
+        assert_eq!(contract.balance_of(asset_id(), holder), 10);
+
+        env::state_write(&contract);
+        assert!(matches!(VersionedState::read(), VersionedState::V2(_)));
+    }
+
+    #[test]
+    fn test_migrate_from_v1_in_batches() {
+        testing_env!(get_context(env::current_account_id()));
+        let nonces = u64::from(MIGRATION_BATCH) + 5;
+        let holders = MIGRATION_BATCH * 2;
+        write_v1_state(nonces, holders);
+
+        let mut contract = NearBridge::migrate();
+        env::state_write(&contract);
+
+        let mut calls = 0;
+        loop {
+            // Each batch runs as its own call on the stored state
+            contract = env::state_read().unwrap();
+            calls += 1;
+            let done = contract.migrate_batch();
+            env::state_write(&contract);
+            if done {
+                break;
+            }
+        }
+        assert!(calls > 4);
+
+        let processed = contract.list_processed(ChainId::Stellar as u8, 0, 100);
+        assert_eq!(processed, (1..=nonces).collect::<Vec<_>>());
+        assert_eq!(contract.total_supply(asset_id()), u128::from(holders) * 10);
+        assert_eq!(contract.list_holders(asset_id(), 0, 100).len(), 100);
+        for holder in 0..holders {
+            let account: AccountId = format!("holder{}.near", holder).parse().unwrap();
+            assert_eq!(contract.balance_of(asset_id(), account), 10);
+        }
+        assert!(contract.pending_migration.is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "State migration in progress")]
+    fn test_unpause_waits_for_migration() {
+        testing_env!(get_context(env::current_account_id()));
+        write_v1_state(u64::from(MIGRATION_BATCH) * 2, 0);
+        let mut contract = NearBridge::migrate();
+        assert!(!contract.migrate_batch());
+
+        testing_env!(get_context("owner.near".parse().unwrap()));
+        contract.unpause(true, true);
+    }
+
+    #[test]
+    fn test_migrate_current_state_is_noop() {
+        testing_env!(get_context(env::current_account_id()));
+        let contract = NearBridge::new("owner.near".parse().unwrap(), vec![[1u8; 32]], 1);
+        env::state_write(&contract);
+
+        let migrated = NearBridge::migrate();
+
+        assert_eq!(migrated.owner, contract.owner);
+        assert!(near_sdk::test_utils::get_logs().is_empty());
+    }
+
+    #[test]
+    fn test_owner_upgrade() {
+        let mut context = get_context("owner.near".parse().unwrap());
+        context.input = b"new wasm".to_vec();
+        testing_env!(context);
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), vec![[1u8; 32]], 1);
+
+        contract.upgrade();
+
+        assert_eq!(near_sdk::test_utils::get_logs(), vec!["Contract upgrade deployed"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Upgrade not authorized")]
+    fn test_upgrade_unauthorized() {
+        let mut context = get_context("mallory.near".parse().unwrap());
+        context.input = b"new wasm".to_vec();
+        testing_env!(context);
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), vec![[1u8; 32]], 1);
+
+        contract.upgrade();
+    }
+
+    #[test]
+    fn test_governance_approved_upgrade() {
+        testing_env!(get_context("owner.near".parse().unwrap()));
+        let mut contract =
+            NearBridge::new("owner.near".parse().unwrap(), guardian_pubkeys(&[1]), 1);
+
+        let mut vaa = GovernanceVAA::new(
+            ChainId::Near,
+            contract_address(env::current_account_id().as_str()),
+            1,
+            GovernanceAction::Upgrade {
+                code_hash: env::sha256_array(b"new wasm"),
+            },
+        );
+        sign_governance(&mut vaa, &[1]);
+        contract.submit_governance(String::from_utf8(vaa.to_bytes().unwrap()).unwrap());
+
+        let mut context = get_context("anyone.near".parse().unwrap());
+        context.input = b"new wasm".to_vec();
+        testing_env!(context);
+        contract.upgrade();
+
+        assert!(contract.approved_upgrade.is_none());
+    }
+
+    /// Asset ids whose raw bytes extend one another
+    const COLLIDING_ASSETS: [&str; 6] = ["", "x", "xm", "xv", "xm\n", "b:x"];
+
+    #[test]
+    fn test_wrapped_balance_prefixes_do_not_overlap() {
+        testing_env!(get_context("owner.near".parse().unwrap()));
+        let prefixes: Vec<Vec<u8>> = COLLIDING_ASSETS
+            .iter()
+            .map(|id| borsh::to_vec(&wrapped_balance_prefix(id)).unwrap())
+            .collect();
+
+        for (i, prefix) in prefixes.iter().enumerate() {
+            for other in &prefixes[i + 1..] {
+                assert!(!prefix.starts_with(other) && !other.starts_with(prefix));
+            }
+        }
+    }
+
+    #[test]
+    fn test_legacy_balances_isolated_across_colliding_assets() {
+        testing_env!(get_context(env::current_account_id()));
+        let mut contract = NearBridge::new("owner.near".parse().unwrap(), vec![[1u8; 32]], 1);
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let bob: AccountId = "bob.near".parse().unwrap();
+
+        for (i, id) in COLLIDING_ASSETS.iter().enumerate() {
+            let mut balances = IterableMap::new(wrapped_balance_prefix(id));
+            balances.insert(alice.clone(), i as u128 + 1);
+            balances.insert(bob.clone(), i as u128 + 100);
+            balances.flush();
+            contract.wrapped_balances.insert(id.to_string(), balances);
+        }
+        contract.wrapped_balances.flush();
+
+        // Reload so every balance is read back from storage
+        env::state_write(&contract);
+        let mut contract: NearBridge = env::state_read().unwrap();
+        let restored = Err(PromiseError::Failed);
+        contract.on_legacy_migrated("xm".to_string(), alice.clone(), U128(50), restored);
+
+        for (i, id) in COLLIDING_ASSETS.iter().enumerate() {
+            let restored = if *id == "xm" { 50 } else { 0 };
+            let alice_balance = contract.balance_of(id.to_string(), alice.clone());
+            assert_eq!(alice_balance, i as u128 + 1 + restored);
+            assert_eq!(contract.balance_of(id.to_string(), bob.clone()), i as u128 + 100);
+        }
     }
 }
@@ -0,0 +1,209 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{UnorderedMap, UnorderedSet};
+use near_sdk::store::{IterableMap, IterableSet};
+use near_sdk::{env, AccountId, Gas};
+
+use bridge_shared::ChainId;
+
+use crate::{wrapped_balance_prefix, Guardian, NearBridge, PauseState, StorageKey};
+
+/// Layout version written by this code
+pub const STATE_VERSION: u32 = 2;
+
+/// Raw storage key of the layout version; absent before version 2
+const STATE_VERSION_KEY: &[u8] = b"v";
+
+pub const GAS_FOR_MIGRATE: Gas = Gas::from_tgas(100);
+
+/// Version 1 entries moved per `migrate_batch` call
+///
+/// An entry costs a few storage reads and writes on each side, which keeps
+/// a batch well under the 300 Tgas transaction limit.
+pub const MIGRATION_BATCH: u32 = 50;
+
+/// Version 1 layout: the first deployed release, built on the deprecated
+/// `near_sdk::collections`
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct NearBridgeV1 {
+    pub owner: AccountId,
+    pub guardians: Vec<Guardian>,
+    pub quorum: u32,
+    pub processed_vaas: UnorderedSet<u64>,
+    pub wrapped_balances: UnorderedMap<String, UnorderedMap<AccountId, u128>>,
+    pub wrapped_supply: UnorderedMap<String, u128>,
+}
+
+/// Version 1 collections still being moved by `migrate_batch`
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct PendingMigration {
+    processed_vaas: UnorderedSet<u64>,
+    /// Processed nonces copied in processing order; they are removed from
+    /// the end once all are copied
+    processed_copied: u64,
+    wrapped_balances: UnorderedMap<String, UnorderedMap<AccountId, u128>>,
+    wrapped_supply: UnorderedMap<String, u128>,
+}
+
+/// Contract state as found in storage
+#[allow(clippy::large_enum_variant)]
+pub enum VersionedState {
+    V1(NearBridgeV1),
+    V2(NearBridge),
+}
+
+impl VersionedState {
+    /// Read the contract state, whatever its layout version
+    pub fn read() -> Self {
+        let state = "Contract is not initialized";
+        match env::storage_read(STATE_VERSION_KEY) {
+            None => VersionedState::V1(env::state_read().expect(state)),
+            Some(version) if version == STATE_VERSION.to_le_bytes() => {
+                VersionedState::V2(env::state_read().expect(state))
+            }
+            Some(_) => env::panic_str("Unknown state version"),
+        }
+    }
+
+    /// Bring the state to the current layout
+    pub fn into_current(self) -> NearBridge {
+        match self {
+            VersionedState::V1(old) => migrate_v1(old),
+            VersionedState::V2(current) => current,
+        }
+    }
+}
+
+pub(crate) fn write_state_version() {
+    env::storage_write(STATE_VERSION_KEY, &STATE_VERSION.to_le_bytes());
+}
+
+/// Switch a version 1 state to the current layout
+///
+/// Only the fixed fields move here. The old collections move in batches
+/// through `migrate_batch`; transfers stay paused until the owner unpauses.
+fn migrate_v1(old: NearBridgeV1) -> NearBridge {
+    let mut state = NearBridge {
+        owner: old.owner,
+        guardians: old.guardians,
+        quorum: old.quorum,
+        guardian_set_index: 0,
+        processed_vaas: IterableSet::new(StorageKey::ProcessedVaas),
+        processed_by_origin: IterableMap::new(StorageKey::ProcessedByOrigin),
+        wrapped_balances: IterableMap::new(StorageKey::WrappedBalances),
+        wrapped_supply: IterableMap::new(StorageKey::WrappedSupply),
+        pauser: None,
+        paused: PauseState {
+            inbound: true,
+            outbound: true,
+        },
+        governance_sequence: 0,
+        token_limits: IterableMap::new(StorageKey::TokenLimits),
+        governor_flows: IterableMap::new(StorageKey::GovernorFlows),
+        queued_mints: IterableMap::new(StorageKey::QueuedMints),
+        lock_nonce: 0,
+        locks: IterableMap::new(StorageKey::Locks),
+        escrowed: IterableMap::new(StorageKey::Escrowed),
+        timelock_nonce: 0,
+        scheduled_actions: IterableMap::new(StorageKey::ScheduledActions),
+        wrapped_tokens: IterableMap::new(StorageKey::WrappedTokens),
+        storage_accounts: IterableMap::new(StorageKey::StorageAccounts),
+        account_storage_usage: 0,
+        pending_claims: IterableMap::new(StorageKey::PendingClaims),
+        outbound_sequence: 0,
+        outbound_messages: IterableMap::new(StorageKey::OutboundMessages),
+        outbound_by_sender: IterableMap::new(StorageKey::OutboundBySender),
+        approved_upgrade: None,
+        pending_migration: Some(PendingMigration {
+            processed_vaas: old.processed_vaas,
+            processed_copied: 0,
+            wrapped_balances: old.wrapped_balances,
+            wrapped_supply: old.wrapped_supply,
+        }),
+        lock_tokens: IterableSet::new(StorageKey::LockTokens),
+    };
+    state.measure_account_storage_usage();
+
+    env::log_str(&format!("State migrated to version {}", STATE_VERSION));
+    state
+}
+
+impl PendingMigration {
+    /// Move up to `budget` entries into `state`
+    ///
+    /// Entries are taken from the end of each old collection, which removes
+    /// them without reordering the rest. Processed nonces are copied first
+    /// and removed afterwards so they keep their processing order.
+    ///
+    /// # Returns
+    /// Whether every old collection is now empty
+    pub(crate) fn step(&mut self, state: &mut NearBridge, mut budget: u32) -> bool {
+        while budget > 0 && self.processed_copied < self.processed_vaas.len() {
+            let nonce = self
+                .processed_vaas
+                .as_vector()
+                .get(self.processed_copied)
+                .unwrap();
+            // Version 1 only processed transfers from Stellar
+            state.mark_processed(ChainId::Stellar, nonce);
+            self.processed_copied += 1;
+            budget -= 1;
+        }
+        while budget > 0 && self.processed_copied == self.processed_vaas.len() {
+            let Some(index) = self.processed_vaas.len().checked_sub(1) else {
+                break;
+            };
+            let nonce = self.processed_vaas.as_vector().get(index).unwrap();
+            self.processed_vaas.remove(&nonce);
+            self.processed_copied -= 1;
+            budget -= 1;
+        }
+
+        while budget > 0 {
+            let Some(asset_id) = last_key(&self.wrapped_supply) else {
+                break;
+            };
+            let supply = self.wrapped_supply.remove(&asset_id).unwrap();
+            state.wrapped_supply.insert(asset_id, supply);
+            budget -= 1;
+        }
+
+        while budget > 0 {
+            let Some(asset_id) = last_key(&self.wrapped_balances) else {
+                break;
+            };
+            let mut old_balances = self.wrapped_balances.get(&asset_id).unwrap();
+            let balances = state
+                .wrapped_balances
+                .entry(asset_id.clone())
+                .or_insert_with(|| IterableMap::new(wrapped_balance_prefix(&asset_id)));
+            while budget > 0 {
+                let Some(account) = last_key(&old_balances) else {
+                    break;
+                };
+                let balance = old_balances.remove(&account).unwrap();
+                balances.insert(account, balance);
+                budget -= 1;
+            }
+
+            if old_balances.is_empty() {
+                self.wrapped_balances.remove(&asset_id);
+            } else {
+                self.wrapped_balances.insert(&asset_id, &old_balances);
+            }
+        }
+
+        self.processed_vaas.is_empty()
+            && self.wrapped_supply.is_empty()
+            && self.wrapped_balances.is_empty()
+    }
+}
+
+/// Last key of a version 1 map
+fn last_key<K, V>(map: &UnorderedMap<K, V>) -> Option<K>
+where
+    K: BorshSerialize + BorshDeserialize,
+    V: BorshSerialize + BorshDeserialize,
+{
+    let index = map.len().checked_sub(1)?;
+    map.keys_as_vector().get(index)
+}
@@ -0,0 +1,40 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, AccountId};
+
+use crate::escrow::{EVENT_STANDARD, EVENT_VERSION};
+
+/// A completed burn, to be released on the recipient chain
+///
+/// The relayer builds the VAA from this record and refunds look it up by
+/// sequence.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OutboundMessage {
+    /// Contract-assigned sequence, one per burn
+    pub sequence: u64,
+    /// Hex-encoded Stellar asset id
+    pub asset_id: String,
+    pub amount: u128,
+    pub sender: AccountId,
+    pub recipient_chain: u8,
+    /// Recipient as given (a strkey for Stellar)
+    pub recipient: String,
+    /// Hex-encoded 32-byte recipient carried in the VAA
+    pub recipient_address: String,
+    /// Block timestamp (seconds)
+    pub timestamp: u64,
+    /// Height of the block that recorded the burn
+    pub block_height: u64,
+}
+
+/// Log a NEP-297 `burn` event for the relayer
+pub fn emit_burn_event(message: &OutboundMessage) {
+    let event = near_sdk::serde_json::json!({
+        "standard": EVENT_STANDARD,
+        "version": EVENT_VERSION,
+        "event": "burn",
+        "data": [message],
+    });
+    env::log_str(&format!("EVENT_JSON:{}", event));
+}
@@ -0,0 +1,55 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, AccountId, NearToken};
+
+/// NEP-145 storage balance of an account
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StorageBalance {
+    pub total: NearToken,
+    pub available: NearToken,
+}
+
+/// NEP-145 deposit bounds; there is no maximum
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StorageBalanceBounds {
+    pub min: NearToken,
+    pub max: Option<NearToken>,
+}
+
+/// Storage paid for and used by a registered account
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct AccountStorage {
+    pub deposit: NearToken,
+    /// Bytes charged, including the registration itself
+    pub used_bytes: u64,
+}
+
+impl AccountStorage {
+    pub fn used_cost(&self) -> NearToken {
+        env::storage_byte_cost().saturating_mul(self.used_bytes as u128)
+    }
+
+    pub fn available(&self) -> NearToken {
+        self.deposit.saturating_sub(self.used_cost())
+    }
+
+    pub fn to_balance(&self) -> StorageBalance {
+        StorageBalance {
+            total: self.deposit,
+            available: self.available(),
+        }
+    }
+}
+
+/// A mint the token contract rejected, held until claimed
+///
+/// Usually the recipient has not registered storage with the token yet.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PendingClaim {
+    pub asset_id: String,
+    pub recipient: AccountId,
+    pub amount: u128,
+}
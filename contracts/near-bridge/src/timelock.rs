@@ -0,0 +1,30 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// Delay between scheduling a parameter change and executing it (48 hours)
+pub const TIMELOCK_DELAY_SECS: u64 = 172_800;
+
+/// Parameter changes that only take effect after the timelock delay
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum TimelockAction {
+    UpdateGuardians {
+        guardians: Vec<[u8; 32]>,
+        quorum: u32,
+    },
+    SetTokenLimit {
+        asset_id: String,
+        daily_limit: u128,
+        big_tx_threshold: u128,
+    },
+}
+
+/// A scheduled `TimelockAction`
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ScheduledAction {
+    pub id: u64,
+    pub action: TimelockAction,
+    /// Earliest block time (seconds) at which the action can be executed
+    pub eta: u64,
+}
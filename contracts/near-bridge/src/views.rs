@@ -0,0 +1,38 @@
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{AccountId, NearToken};
+
+use crate::{PauseState, WrappedTokenInfo};
+
+/// Largest page returned by the list views
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+/// A wrapped asset with its total supply
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AssetView {
+    pub token: WrappedTokenInfo,
+    pub total_supply: u128,
+}
+
+/// Bridge settings in one view
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BridgeConfig {
+    pub owner: AccountId,
+    pub pauser: Option<AccountId>,
+    pub guardians: Vec<[u8; 32]>,
+    pub quorum: u32,
+    /// Number of guardian set updates executed so far
+    pub guardian_set_index: u32,
+    pub paused: PauseState,
+    /// Minimum NEP-145 deposit
+    pub storage_deposit_min: NearToken,
+    /// Deposit required to deploy a wrapped token
+    pub token_deploy_deposit: NearToken,
+}
+
+/// Indices `cursor..end` of a page over `len` items
+pub fn page(cursor: u32, limit: u32, len: u32) -> std::ops::Range<u32> {
+    let end = cursor.saturating_add(limit.min(MAX_PAGE_SIZE)).min(len);
+    cursor.min(end)..end
+}
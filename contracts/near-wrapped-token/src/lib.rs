@@ -0,0 +1,300 @@
+//! NEP-141 token for a Stellar asset bridged to NEAR
+//!
+//! Deployed by the bridge as a sub-account per asset
+//! (`usdc-stellar.bridge.near`). The deploying bridge is the controller and
+//! the only account allowed to mint and burn.
+
+use near_contract_standards::fungible_token::core::FungibleTokenCore;
+use near_contract_standards::fungible_token::events::{FtBurn, FtMint};
+use near_contract_standards::fungible_token::metadata::{
+    FungibleTokenMetadata, FungibleTokenMetadataProvider,
+};
+use near_contract_standards::fungible_token::resolver::FungibleTokenResolver;
+use near_contract_standards::fungible_token::FungibleToken;
+use near_contract_standards::storage_management::{
+    StorageBalance, StorageBalanceBounds, StorageManagement,
+};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LazyOption;
+use near_sdk::json_types::U128;
+use near_sdk::{env, log, near_bindgen, AccountId, NearToken, PanicOnDefault, PromiseOrValue};
+
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct WrappedToken {
+    /// Bridge contract allowed to mint and burn
+    pub controller: AccountId,
+
+    /// NEP-141 balances and storage registrations
+    token: FungibleToken,
+
+    /// NEP-148 metadata
+    metadata: LazyOption<FungibleTokenMetadata>,
+}
+
+#[near_bindgen]
+impl WrappedToken {
+    /// Initialize with the caller as controller
+    #[init]
+    pub fn new(metadata: FungibleTokenMetadata) -> Self {
+        assert!(!env::state_exists(), "Already initialized");
+        metadata.assert_valid();
+
+        Self {
+            controller: env::predecessor_account_id(),
+            token: FungibleToken::new(b"a".to_vec()),
+            metadata: LazyOption::new(b"m".to_vec(), Some(&metadata)),
+        }
+    }
+
+    /// Mint `amount` to `account_id` (controller only)
+    ///
+    /// The account must already be registered with `storage_deposit`.
+    pub fn mint(&mut self, account_id: AccountId, amount: U128) {
+        self.assert_controller();
+
+        self.token.internal_deposit(&account_id, amount.0);
+        FtMint {
+            owner_id: &account_id,
+            amount,
+            memo: Some("Bridged from Stellar"),
+        }
+        .emit();
+    }
+
+    /// Burn `amount` from `account_id` (controller only)
+    pub fn burn(&mut self, account_id: AccountId, amount: U128) {
+        self.assert_controller();
+
+        self.token.internal_withdraw(&account_id, amount.0);
+        FtBurn {
+            owner_id: &account_id,
+            amount,
+            memo: Some("Bridged to Stellar"),
+        }
+        .emit();
+    }
+
+    fn assert_controller(&self) {
+        assert_eq!(
+            env::predecessor_account_id(),
+            self.controller,
+            "Only the bridge can call this method"
+        );
+    }
+}
+
+#[near_bindgen]
+impl FungibleTokenCore for WrappedToken {
+    #[payable]
+    fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>) {
+        self.token.ft_transfer(receiver_id, amount, memo)
+    }
+
+    #[payable]
+    fn ft_transfer_call(
+        &mut self,
+        receiver_id: AccountId,
+        amount: U128,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        self.token.ft_transfer_call(receiver_id, amount, memo, msg)
+    }
+
+    fn ft_total_supply(&self) -> U128 {
+        self.token.ft_total_supply()
+    }
+
+    fn ft_balance_of(&self, account_id: AccountId) -> U128 {
+        self.token.ft_balance_of(account_id)
+    }
+}
+
+#[near_bindgen]
+impl FungibleTokenResolver for WrappedToken {
+    #[private]
+    fn ft_resolve_transfer(
+        &mut self,
+        sender_id: AccountId,
+        receiver_id: AccountId,
+        amount: U128,
+    ) -> U128 {
+        let (used_amount, burned_amount) =
+            self.token
+                .internal_ft_resolve_transfer(&sender_id, receiver_id, amount);
+        if burned_amount > 0 {
+            log!("Account @{} burned {}", sender_id, burned_amount);
+        }
+        used_amount.into()
+    }
+}
+
+#[near_bindgen]
+impl StorageManagement for WrappedToken {
+    #[payable]
+    fn storage_deposit(
+        &mut self,
+        account_id: Option<AccountId>,
+        registration_only: Option<bool>,
+    ) -> StorageBalance {
+        self.token.storage_deposit(account_id, registration_only)
+    }
+
+    #[payable]
+    fn storage_withdraw(&mut self, amount: Option<NearToken>) -> StorageBalance {
+        self.token.storage_withdraw(amount)
+    }
+
+    /// Forcing is refused while the account holds tokens: burning them here
+    /// would leave the bridge's supply for this asset too high
+    #[payable]
+    fn storage_unregister(&mut self, force: Option<bool>) -> bool {
+        if force.unwrap_or(false) {
+            let balance = self.token.ft_balance_of(env::predecessor_account_id());
+            assert!(
+                balance.0 == 0,
+                "Cannot force unregister with a balance; bridge it back first"
+            );
+        }
+        if let Some((account_id, balance)) = self.token.internal_storage_unregister(force) {
+            log!("Closed @{} with {}", account_id, balance);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+        self.token.storage_balance_bounds()
+    }
+
+    fn storage_balance_of(&self, account_id: AccountId) -> Option<StorageBalance> {
+        self.token.storage_balance_of(account_id)
+    }
+}
+
+#[near_bindgen]
+impl FungibleTokenMetadataProvider for WrappedToken {
+    fn ft_metadata(&self) -> FungibleTokenMetadata {
+        self.metadata.get().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_contract_standards::fungible_token::metadata::FT_METADATA_SPEC;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::testing_env;
+
+    fn metadata() -> FungibleTokenMetadata {
+        FungibleTokenMetadata {
+            spec: FT_METADATA_SPEC.to_string(),
+            name: "USD Coin (Stellar)".to_string(),
+            symbol: "USDC".to_string(),
+            icon: None,
+            reference: None,
+            reference_hash: None,
+            decimals: 7,
+        }
+    }
+
+    fn set_caller(account: &str, deposit: NearToken) {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(account.parse().unwrap())
+            .attached_deposit(deposit)
+            .build());
+    }
+
+    /// Token deployed by `bridge.near` with `alice.near` registered
+    fn setup() -> WrappedToken {
+        set_caller("bridge.near", NearToken::from_yoctonear(0));
+        let mut token = WrappedToken::new(metadata());
+
+        set_caller("alice.near", token.storage_balance_bounds().min);
+        token.storage_deposit(None, None);
+        token
+    }
+
+    fn alice() -> AccountId {
+        "alice.near".parse().unwrap()
+    }
+
+    #[test]
+    fn test_new_sets_controller_and_metadata() {
+        let token = setup();
+        assert_eq!(
+            token.controller,
+            "bridge.near".parse::<AccountId>().unwrap()
+        );
+        assert_eq!(token.ft_metadata().symbol, "USDC");
+        assert_eq!(token.ft_metadata().decimals, 7);
+        assert_eq!(token.ft_total_supply().0, 0);
+    }
+
+    #[test]
+    fn test_controller_mints_and_burns() {
+        let mut token = setup();
+
+        set_caller("bridge.near", NearToken::from_yoctonear(0));
+        token.mint(alice(), U128(1_000));
+        assert_eq!(token.ft_balance_of(alice()).0, 1_000);
+
+        token.burn(alice(), U128(400));
+        assert_eq!(token.ft_balance_of(alice()).0, 600);
+        assert_eq!(token.ft_total_supply().0, 600);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the bridge can call this method")]
+    fn test_only_controller_can_mint() {
+        let mut token = setup();
+        set_caller("alice.near", NearToken::from_yoctonear(0));
+        token.mint(alice(), U128(1_000));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the bridge can call this method")]
+    fn test_only_controller_can_burn() {
+        let mut token = setup();
+        set_caller("bridge.near", NearToken::from_yoctonear(0));
+        token.mint(alice(), U128(1_000));
+
+        set_caller("alice.near", NearToken::from_yoctonear(0));
+        token.burn(alice(), U128(1_000));
+    }
+
+    #[test]
+    fn test_storage_registration() {
+        let mut token = setup();
+        let bounds = token.storage_balance_bounds();
+        assert_eq!(token.storage_balance_of(alice()).unwrap().total, bounds.min);
+        assert!(token
+            .storage_balance_of("bob.near".parse().unwrap())
+            .is_none());
+
+        set_caller("alice.near", NearToken::from_yoctonear(1));
+        assert!(token.storage_unregister(None));
+        assert!(token.storage_balance_of(alice()).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot force unregister with a balance")]
+    fn test_force_unregister_with_balance_is_rejected() {
+        let mut token = setup();
+        set_caller("bridge.near", NearToken::from_yoctonear(0));
+        token.mint(alice(), U128(1_000));
+
+        set_caller("alice.near", NearToken::from_yoctonear(1));
+        token.storage_unregister(Some(true));
+    }
+
+    #[test]
+    fn test_force_unregister_without_balance() {
+        let mut token = setup();
+        set_caller("alice.near", NearToken::from_yoctonear(1));
+        assert!(token.storage_unregister(Some(true)));
+        assert_eq!(token.ft_total_supply().0, 0);
+    }
+}
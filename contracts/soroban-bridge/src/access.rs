@@ -0,0 +1,206 @@
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, Vec};
+
+use crate::{set_persistent, Error};
+
+/// Roles that gate privileged entrypoints
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum Role {
+    /// Approves `AdminAction` proposals (M-of-N)
+    Owner = 0,
+    /// May pause the bridge (owners may too)
+    Pauser = 1,
+    /// Sets fees and withdraws them to the treasury
+    FeeManager = 2,
+    /// Registers wrapped assets and sets governor limits
+    TokenRegistrar = 3,
+}
+
+/// Sensitive operations that need `threshold` owner approvals
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum AdminAction {
+    GrantRole(Role, Address),
+    RevokeRole(Role, Address),
+    /// Set the number of owner approvals required
+    SetThreshold(u32),
+    /// Propose a new operator admin (completed by `accept_admin`)
+    TransferAdmin(Address),
+    /// Upgrade the contract to an uploaded WASM (hash)
+    Upgrade(BytesN<32>),
+    SetTreasury(Address),
+    /// Resume transfers (inbound, outbound)
+    Unpause(bool, bool),
+    /// Schedule a guardian set replacement (guardians, quorum), timelocked
+    UpdateGuardians(Vec<BytesN<32>>, u32),
+}
+
+/// An `AdminAction` awaiting owner approvals
+///
+/// Proposals are removed once executed.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Proposal {
+    pub id: u64,
+    pub action: AdminAction,
+    pub proposer: Address,
+    pub approvals: Vec<Address>,
+}
+
+/// Storage keys for roles and proposals
+#[contracttype]
+pub enum AccessKey {
+    /// Role -> Vec<Address>
+    Members(Role),
+    /// Owner approvals required to execute a proposal
+    Threshold,
+    /// Latest proposal id
+    ProposalNonce,
+    /// Proposal id -> Proposal (persistent)
+    Proposal(u64),
+}
+
+pub fn members(env: &Env, role: Role) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&AccessKey::Members(role))
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn has_role(env: &Env, role: Role, account: &Address) -> bool {
+    members(env, role).contains(account)
+}
+
+/// Authenticate `caller` and check it holds `role`
+pub fn require_role(env: &Env, caller: &Address, role: Role) -> Result<(), Error> {
+    caller.require_auth();
+
+    if !has_role(env, role, caller) {
+        return Err(Error::NotAuthorized);
+    }
+    Ok(())
+}
+
+pub fn grant_role(env: &Env, role: Role, account: &Address) {
+    let mut accounts = members(env, role);
+    if accounts.contains(account) {
+        return;
+    }
+    accounts.push_back(account.clone());
+    env.storage()
+        .instance()
+        .set(&AccessKey::Members(role), &accounts);
+
+    env.events().publish(
+        (symbol_short!("role"), symbol_short!("granted")),
+        (role, account.clone()),
+    );
+}
+
+/// Revoke `role`; the owner set may not drop below the threshold
+pub fn revoke_role(env: &Env, role: Role, account: &Address) -> Result<(), Error> {
+    let mut accounts = members(env, role);
+    let Some(index) = accounts.first_index_of(account) else {
+        return Ok(());
+    };
+    if role == Role::Owner && accounts.len() <= threshold(env) {
+        return Err(Error::InvalidThreshold);
+    }
+
+    accounts.remove(index);
+    env.storage()
+        .instance()
+        .set(&AccessKey::Members(role), &accounts);
+
+    env.events().publish(
+        (symbol_short!("role"), symbol_short!("revoked")),
+        (role, account.clone()),
+    );
+    Ok(())
+}
+
+pub fn threshold(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&AccessKey::Threshold)
+        .unwrap_or(1)
+}
+
+pub fn set_threshold(env: &Env, threshold: u32) -> Result<(), Error> {
+    if threshold == 0 || threshold > members(env, Role::Owner).len() {
+        return Err(Error::InvalidThreshold);
+    }
+
+    env.storage()
+        .instance()
+        .set(&AccessKey::Threshold, &threshold);
+
+    env.events()
+        .publish((symbol_short!("role"), symbol_short!("thresh")), threshold);
+    Ok(())
+}
+
+pub fn proposal(env: &Env, id: u64) -> Option<Proposal> {
+    env.storage().persistent().get(&AccessKey::Proposal(id))
+}
+
+/// The proposal `id` if it still awaits execution
+///
+/// Ids are handed out in sequence, so a missing id up to the latest one
+/// belongs to a proposal that was executed and removed.
+pub fn pending_proposal(env: &Env, id: u64) -> Result<Proposal, Error> {
+    if let Some(proposal) = proposal(env, id) {
+        return Ok(proposal);
+    }
+
+    let latest: u64 = env
+        .storage()
+        .instance()
+        .get(&AccessKey::ProposalNonce)
+        .unwrap_or(0);
+    if id == 0 || id > latest {
+        return Err(Error::ProposalNotFound);
+    }
+    Err(Error::ProposalAlreadyExecuted)
+}
+
+pub fn set_proposal(env: &Env, proposal: &Proposal) {
+    set_persistent(env, &AccessKey::Proposal(proposal.id), proposal);
+}
+
+pub fn remove_proposal(env: &Env, id: u64) {
+    env.storage().persistent().remove(&AccessKey::Proposal(id));
+}
+
+/// Create a proposal approved by its proposer
+pub fn create_proposal(env: &Env, proposer: &Address, action: AdminAction) -> Proposal {
+    let id: u64 = env
+        .storage()
+        .instance()
+        .get(&AccessKey::ProposalNonce)
+        .unwrap_or(0)
+        + 1;
+
+    let mut approvals = Vec::new(env);
+    approvals.push_back(proposer.clone());
+    let proposal = Proposal {
+        id,
+        action,
+        proposer: proposer.clone(),
+        approvals,
+    };
+    set_proposal(env, &proposal);
+    env.storage().instance().set(&AccessKey::ProposalNonce, &id);
+    proposal
+}
+
+/// Approvals on `proposal` from accounts that are still owners
+pub fn current_approvals(env: &Env, proposal: &Proposal) -> u32 {
+    let owners = members(env, Role::Owner);
+    proposal
+        .approvals
+        .iter()
+        .filter(|approver| owners.contains(approver))
+        .count() as u32
+}
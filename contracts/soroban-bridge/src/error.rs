@@ -0,0 +1,79 @@
+use soroban_sdk::contracterror;
+
+/// Errors returned by the bridge contract
+///
+/// Codes are part of the public interface: never renumber or reuse a code,
+/// only append new variants.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    /// `initialize` was already called
+    AlreadyInitialized = 1,
+    /// The contract has not been initialized
+    NotInitialized = 2,
+    /// Quorum is zero or larger than the guardian set
+    InvalidQuorum = 3,
+    /// Amount must be positive
+    InvalidAmount = 4,
+    /// A lock already exists for the next nonce
+    LockAlreadyExists = 5,
+    /// No lock exists for the nonce
+    LockNotFound = 6,
+    /// The lock's TransferStatus does not allow this operation
+    InvalidStatusTransition = 7,
+    /// Inbound transfers (release, refund) are paused
+    InboundPaused = 8,
+    /// Outbound transfers (lock) are paused
+    OutboundPaused = 9,
+    /// Caller lacks the required role
+    NotAuthorized = 10,
+    /// Guardian signatures do not reach the quorum
+    InsufficientSignatures = 11,
+    /// Governance VAA sequence was already consumed
+    GovernanceReplay = 12,
+    /// A governor-queued release already exists for the lock
+    ReleaseAlreadyQueued = 13,
+    /// No governor-queued release exists for the lock
+    ReleaseNotQueued = 14,
+    /// The queued release's delay has not passed yet
+    ReleaseDelayed = 15,
+    /// Governor limits must be positive
+    InvalidLimit = 16,
+    /// The lock cannot be cancelled before the refund timeout
+    RefundTimeoutNotReached = 17,
+    /// No admin transfer has been proposed
+    NoPendingAdmin = 18,
+    /// Stored data is newer than this contract code understands
+    UnsupportedStorageVersion = 19,
+    /// A wrapped token already exists for the asset or token address
+    WrappedAssetExists = 20,
+    /// No wrapped token exists for the asset
+    WrappedAssetNotFound = 21,
+    /// The bridge is not the admin of the token contract
+    NotTokenAdmin = 22,
+    /// The inbound transfer was already minted
+    TransferAlreadyProcessed = 23,
+    /// Fee basis points above 10_000 or negative minimum fee
+    InvalidFee = 24,
+    /// The amount does not cover the bridge fee
+    AmountBelowFee = 25,
+    /// No treasury address has been set
+    NoTreasury = 26,
+    /// A statistics counter overflowed
+    ArithmeticOverflow = 27,
+    /// No proposal exists for the id
+    ProposalNotFound = 28,
+    /// The proposal was already executed
+    ProposalAlreadyExecuted = 29,
+    /// The owner already approved the proposal
+    AlreadyApproved = 30,
+    /// The proposal lacks the required owner approvals
+    ThresholdNotReached = 31,
+    /// Threshold is zero or larger than the owner set
+    InvalidThreshold = 32,
+    /// No scheduled action exists for the timelock id
+    TimelockNotFound = 33,
+    /// The timelock delay has not passed yet
+    TimelockNotReady = 34,
+}
@@ -0,0 +1,79 @@
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::Error;
+
+/// Fee denominator: `bps` are parts per 10_000
+pub const BPS_DENOMINATOR: i128 = 10_000;
+
+/// Per-token bridge fee charged on `lock_tokens`
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeeConfig {
+    /// Proportional fee in basis points
+    pub bps: u32,
+    /// Minimum fee, in token units
+    pub min_fee: i128,
+}
+
+/// Storage keys for fee data
+#[contracttype]
+pub enum FeeKey {
+    /// Token -> FeeConfig
+    Config(Address),
+    /// Token -> fees accrued and not yet withdrawn
+    Accrued(Address),
+    /// Address receiving withdrawn fees
+    Treasury,
+}
+
+pub fn fee_config(env: &Env, token: &Address) -> Option<FeeConfig> {
+    env.storage().instance().get(&FeeKey::Config(token.clone()))
+}
+
+pub fn set_fee_config(env: &Env, token: &Address, config: &FeeConfig) {
+    env.storage()
+        .instance()
+        .set(&FeeKey::Config(token.clone()), config);
+}
+
+/// Fee charged for locking `amount` of `token`
+///
+/// The fee must leave a positive amount to bridge.
+pub fn compute_fee(env: &Env, token: &Address, amount: i128) -> Result<i128, Error> {
+    let fee = match fee_config(env, token) {
+        Some(config) => {
+            let proportional = amount
+                .checked_mul(config.bps as i128)
+                .ok_or(Error::InvalidAmount)?
+                / BPS_DENOMINATOR;
+            proportional.max(config.min_fee)
+        }
+        None => 0,
+    };
+
+    if fee >= amount {
+        return Err(Error::AmountBelowFee);
+    }
+    Ok(fee)
+}
+
+pub fn accrued(env: &Env, token: &Address) -> i128 {
+    env.storage()
+        .instance()
+        .get(&FeeKey::Accrued(token.clone()))
+        .unwrap_or(0)
+}
+
+pub fn set_accrued(env: &Env, token: &Address, amount: i128) {
+    env.storage()
+        .instance()
+        .set(&FeeKey::Accrued(token.clone()), &amount);
+}
+
+pub fn treasury(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&FeeKey::Treasury)
+}
+
+pub fn set_treasury(env: &Env, treasury: &Address) {
+    env.storage().instance().set(&FeeKey::Treasury, treasury);
+}
@@ -0,0 +1,153 @@
+use soroban_sdk::{contracttype, xdr::ToXdr, Address, Bytes, BytesN, Env, Vec};
+
+use crate::{DataKey, Error, TransferStatus};
+
+/// Guardian signature over a message digest (pubkey + signature pair)
+#[contracttype]
+#[derive(Clone)]
+pub struct GuardianSignature {
+    pub guardian_pubkey: BytesN<32>,
+    pub signature: BytesN<64>,
+}
+
+/// Actions the guardian set can authorize directly with a governance VAA
+#[contracttype]
+#[derive(Clone)]
+pub enum GovernanceAction {
+    /// Set the pause switches (inbound, outbound)
+    SetPause(bool, bool),
+    /// Cancel a release queued by the governor (lock nonce)
+    CancelQueuedRelease(u64),
+    /// Upgrade the contract to an uploaded WASM (hash)
+    Upgrade(BytesN<32>),
+    /// Cancel a scheduled timelock action (timelock id)
+    CancelTimelock(u64),
+}
+
+/// Governance VAA - a guardian-signed instruction for this contract
+#[contracttype]
+#[derive(Clone)]
+pub struct GovernanceVaa {
+    /// Strictly increasing sequence (replay protection)
+    pub sequence: u64,
+    pub action: GovernanceAction,
+    pub signatures: Vec<GuardianSignature>,
+}
+
+/// Message body guardians sign, binding the action to this contract
+#[contracttype]
+#[derive(Clone)]
+struct GovernanceBody {
+    contract: Address,
+    sequence: u64,
+    action: GovernanceAction,
+}
+
+/// Message body guardians sign to attest the outcome of a lock
+#[contracttype]
+#[derive(Clone)]
+struct AttestationBody {
+    contract: Address,
+    nonce: u64,
+    status: TransferStatus,
+}
+
+/// Compute the digest guardians sign to attest lock `nonce` reached `status`
+/// (`Delivered` on NEAR, or `Cancelled` and never to be delivered)
+pub fn attestation_digest(
+    env: &Env,
+    contract: &Address,
+    nonce: u64,
+    status: TransferStatus,
+) -> BytesN<32> {
+    let body = AttestationBody {
+        contract: contract.clone(),
+        nonce,
+        status,
+    };
+    env.crypto().sha256(&body.to_xdr(env)).into()
+}
+
+/// Compute the digest guardians sign for a governance action
+pub fn governance_digest(
+    env: &Env,
+    contract: &Address,
+    sequence: u64,
+    action: &GovernanceAction,
+) -> BytesN<32> {
+    let body = GovernanceBody {
+        contract: contract.clone(),
+        sequence,
+        action: action.clone(),
+    };
+    env.crypto().sha256(&body.to_xdr(env)).into()
+}
+
+/// Verify that `signatures` over `digest` reach the guardian quorum
+///
+/// Unknown or duplicate guardians are ignored; an invalid signature
+/// from a known guardian aborts the call (host crypto error).
+pub fn verify_guardian_signatures(
+    env: &Env,
+    digest: &BytesN<32>,
+    signatures: &Vec<GuardianSignature>,
+) -> Result<(), Error> {
+    let guardians: Vec<BytesN<32>> = env
+        .storage()
+        .instance()
+        .get(&DataKey::Guardians)
+        .ok_or(Error::NotInitialized)?;
+    let quorum: u32 = env
+        .storage()
+        .instance()
+        .get(&DataKey::Quorum)
+        .ok_or(Error::NotInitialized)?;
+
+    let message = Bytes::from(digest.clone());
+    let mut signers: Vec<BytesN<32>> = Vec::new(env);
+
+    for sig in signatures.iter() {
+        if !guardians.contains(&sig.guardian_pubkey) || signers.contains(&sig.guardian_pubkey) {
+            continue;
+        }
+
+        env.crypto()
+            .ed25519_verify(&sig.guardian_pubkey, &message, &sig.signature);
+        signers.push_back(sig.guardian_pubkey);
+    }
+
+    if signers.len() < quorum {
+        return Err(Error::InsufficientSignatures);
+    }
+    Ok(())
+}
+
+/// Verify a governance VAA and mark its sequence as consumed
+///
+/// # Returns
+/// The authorized action
+pub fn consume_governance_vaa(env: &Env, vaa: &GovernanceVaa) -> Result<GovernanceAction, Error> {
+    let last_sequence: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::GovernanceSequence)
+        .unwrap_or(0);
+
+    if vaa.sequence <= last_sequence {
+        return Err(Error::GovernanceReplay);
+    }
+
+    let digest = governance_digest(
+        env,
+        &env.current_contract_address(),
+        vaa.sequence,
+        &vaa.action,
+    );
+    verify_guardian_signatures(env, &digest, &vaa.signatures)?;
+
+    env.storage()
+        .instance()
+        .set(&DataKey::GovernanceSequence, &vaa.sequence);
+
+    Ok(vaa.action.clone())
+}
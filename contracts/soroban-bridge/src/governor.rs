@@ -0,0 +1,156 @@
+use soroban_sdk::{contracttype, Address, Env, Vec};
+
+use crate::set_persistent;
+
+/// Length of the rolling rate-limit window (24 hours)
+pub const WINDOW_SECS: u64 = 86_400;
+
+/// Delay before a queued release can be executed (24 hours)
+pub const QUEUE_DELAY_SECS: u64 = 86_400;
+
+/// Releases within the same hour share one `Flow` entry
+pub const FLOW_BUCKET_SECS: u64 = 3_600;
+
+/// Per-token outbound limits
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenLimit {
+    /// Maximum amount released in any rolling 24h window
+    pub daily_limit: i128,
+    /// Releases of at least this amount are always queued
+    pub big_tx_threshold: i128,
+}
+
+/// A release recorded in the rolling window
+#[contracttype]
+#[derive(Clone)]
+pub struct Flow {
+    pub timestamp: u64,
+    pub amount: i128,
+}
+
+/// A release held back by the governor
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueuedRelease {
+    pub nonce: u64,
+    /// NEAR burn sequence the release settles
+    pub burn_sequence: u64,
+    pub recipient: Address,
+    pub amount: i128,
+    pub release_after: u64,
+}
+
+/// Storage keys for governor data
+#[contracttype]
+pub enum GovernorKey {
+    /// Token -> TokenLimit
+    Limit(Address),
+    /// Token -> Vec<Flow> within the current window (persistent)
+    Flows(Address),
+    /// Lock nonce -> QueuedRelease (persistent)
+    Queued(u64),
+}
+
+pub fn token_limit(env: &Env, token: &Address) -> Option<TokenLimit> {
+    env.storage()
+        .instance()
+        .get(&GovernorKey::Limit(token.clone()))
+}
+
+pub fn set_token_limit(env: &Env, token: &Address, limit: &TokenLimit) {
+    env.storage()
+        .instance()
+        .set(&GovernorKey::Limit(token.clone()), limit);
+}
+
+/// Flows for `token` that are still inside the rolling window
+fn current_flows(env: &Env, token: &Address) -> Vec<Flow> {
+    let now = env.ledger().timestamp();
+    let flows: Vec<Flow> = env
+        .storage()
+        .persistent()
+        .get(&GovernorKey::Flows(token.clone()))
+        .unwrap_or(Vec::new(env));
+
+    let mut current = Vec::new(env);
+    for flow in flows.iter() {
+        if flow.timestamp + WINDOW_SECS > now {
+            current.push_back(flow);
+        }
+    }
+    current
+}
+
+/// Amount of `token` released in the current rolling window
+pub fn window_usage(env: &Env, token: &Address) -> i128 {
+    current_flows(env, token)
+        .iter()
+        .map(|flow| flow.amount)
+        .sum()
+}
+
+/// Whether a release of `amount` must be queued instead of executed
+pub fn should_queue(env: &Env, token: &Address, amount: i128) -> bool {
+    match token_limit(env, token) {
+        Some(limit) => {
+            amount >= limit.big_tx_threshold
+                || window_usage(env, token) + amount > limit.daily_limit
+        }
+        None => false,
+    }
+}
+
+/// Record an executed release in the rolling window
+///
+/// Flows that left the window are dropped. Releases in the same hour share
+/// one entry stamped with the latest of them, so at most ~25 entries are
+/// kept and an entry leaves the window with its last release, never early.
+pub fn record_flow(env: &Env, token: &Address, amount: i128) {
+    if token_limit(env, token).is_none() {
+        return;
+    }
+
+    let now = env.ledger().timestamp();
+    let mut flows = current_flows(env, token);
+    match flows.last() {
+        Some(mut last) if last.timestamp / FLOW_BUCKET_SECS == now / FLOW_BUCKET_SECS => {
+            last.timestamp = now;
+            last.amount += amount;
+            flows.set(flows.len() - 1, last);
+        }
+        _ => flows.push_back(Flow {
+            timestamp: now,
+            amount,
+        }),
+    }
+    set_persistent(env, &GovernorKey::Flows(token.clone()), &flows);
+}
+
+pub fn queued_release(env: &Env, nonce: u64) -> Option<QueuedRelease> {
+    env.storage().persistent().get(&GovernorKey::Queued(nonce))
+}
+
+pub fn queue_release(
+    env: &Env,
+    nonce: u64,
+    burn_sequence: u64,
+    recipient: &Address,
+    amount: i128,
+) -> QueuedRelease {
+    let queued = QueuedRelease {
+        nonce,
+        burn_sequence,
+        recipient: recipient.clone(),
+        amount,
+        release_after: env.ledger().timestamp() + QUEUE_DELAY_SECS,
+    };
+    set_persistent(env, &GovernorKey::Queued(nonce), &queued);
+    queued
+}
+
+pub fn remove_queued_release(env: &Env, nonce: u64) {
+    env.storage()
+        .persistent()
+        .remove(&GovernorKey::Queued(nonce));
+}
@@ -0,0 +1,80 @@
+use soroban_sdk::{contracttype, Address, Env, Vec};
+
+use crate::{set_persistent, DataKey, LockBook, LockRecord};
+
+/// Largest page returned by the history views
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+/// Persistent storage keys for the per-sender lock index
+#[contracttype]
+pub enum HistoryKey {
+    /// Sender -> number of locks made
+    SenderCount(Address),
+    /// (Sender, position) -> lock nonce
+    SenderLock(Address, u32),
+}
+
+pub fn get_lock(env: &Env, nonce: u64) -> Option<LockRecord> {
+    env.storage().persistent().get(&LockBook::Lock(nonce))
+}
+
+pub fn has_lock(env: &Env, nonce: u64) -> bool {
+    env.storage().persistent().has(&LockBook::Lock(nonce))
+}
+
+pub fn put_lock(env: &Env, record: &LockRecord) {
+    set_persistent(env, &LockBook::Lock(record.nonce), record);
+}
+
+pub fn sender_lock_count(env: &Env, sender: &Address) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&HistoryKey::SenderCount(sender.clone()))
+        .unwrap_or(0)
+}
+
+/// Append lock `nonce` to `sender`'s index
+pub fn index_lock(env: &Env, sender: &Address, nonce: u64) {
+    let count = sender_lock_count(env, sender);
+
+    set_persistent(env, &HistoryKey::SenderLock(sender.clone(), count), &nonce);
+    set_persistent(env, &HistoryKey::SenderCount(sender.clone()), &(count + 1));
+}
+
+/// Locks with nonce >= `from_nonce`, in nonce order
+pub fn list_locks(env: &Env, from_nonce: u64, limit: u32) -> Vec<LockRecord> {
+    let last_nonce: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::LockNonce)
+        .unwrap_or(0);
+    let limit = limit.min(MAX_PAGE_SIZE);
+
+    let mut locks = Vec::new(env);
+    let mut nonce = from_nonce.max(1);
+    while nonce <= last_nonce && locks.len() < limit {
+        if let Some(record) = get_lock(env, nonce) {
+            locks.push_back(record);
+        }
+        nonce += 1;
+    }
+    locks
+}
+
+/// `sender`'s locks from index position `cursor`, oldest first
+pub fn locks_by_sender(env: &Env, sender: &Address, cursor: u32, limit: u32) -> Vec<LockRecord> {
+    let count = sender_lock_count(env, sender);
+    let end = cursor.saturating_add(limit.min(MAX_PAGE_SIZE)).min(count);
+
+    let mut locks = Vec::new(env);
+    for position in cursor..end {
+        let nonce: Option<u64> = env
+            .storage()
+            .persistent()
+            .get(&HistoryKey::SenderLock(sender.clone(), position));
+        if let Some(record) = nonce.and_then(|nonce| get_lock(env, nonce)) {
+            locks.push_back(record);
+        }
+    }
+    locks
+}
@@ -2,30 +2,132 @@
 #![no_std]
 
 use soroban_sdk::{
-    contract, contractimpl, contracttype, log, token, Address, BytesN, Env, String, Symbol, symbol_short,
+    contract, contractimpl, contracttype, log, token, token::StellarAssetClient, Address, BytesN,
+    Env, IntoVal, Symbol, symbol_short, Val, Vec,
 };
 
+mod access;
+mod error;
+mod fees;
+mod governance;
+mod governor;
+mod history;
+mod migration;
+mod stats;
+mod timelock;
+mod wrapped;
+
+pub use access::{AdminAction, Proposal, Role};
+pub use error::Error;
+pub use fees::FeeConfig;
+pub use governance::{GovernanceAction, GovernanceVaa, GuardianSignature};
+pub use governor::{QueuedRelease, TokenLimit};
+pub use history::MAX_PAGE_SIZE;
+pub use migration::{MIGRATION_BATCH, STORAGE_VERSION};
+pub use stats::TokenStats;
+pub use timelock::{TimelockAction, TimelockEntry, TIMELOCK_DELAY_SECS};
+pub use wrapped::{AssetMeta, BurnRecord, WrappedTransfer};
+
 /// Storage keys for persistent data
 #[contracttype]
 #[derive(Clone)]
 pub enum DataKey {
     /// Nonce counter for outgoing locks
     LockNonce,
-    /// Admin address
+    /// Operator admin (submits releases, runs migrations)
     Admin,
+    /// Authorized guardian public keys (ed25519)
+    Guardians,
+    /// Required guardian signature quorum
+    Quorum,
+    /// Last consumed governance VAA sequence
+    GovernanceSequence,
+    /// Current PauseState
+    Paused,
+    /// Proposed new admin awaiting `accept_admin`
+    PendingAdmin,
+    /// Storage layout version (see `migration`)
+    StorageVersion,
+    /// Progress of a migration step that walks the locks
+    MigrationCursor,
+    /// NEAR burn sequence -> lock nonce released for it (persistent)
+    BurnReleased(u64),
+}
+
+/// Emergency stop switches
+///
+/// `inbound` blocks `release_tokens` (NEAR -> Stellar),
+/// `outbound` blocks `lock_tokens` (Stellar -> NEAR).
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PauseState {
+    pub inbound: bool,
+    pub outbound: bool,
 }
 
 /// Structure to track bridge statistics
+///
+/// Amounts are tracked per token in `TokenStats` (see `view_token_stats`).
 #[contracttype]
 #[derive(Clone)]
 pub struct BridgeStats {
     pub total_locked: u64,   // Total number of lock transactions
     pub total_released: u64, // Total number of release transactions
-    pub total_volume: i128,  // Total volume transferred
 }
 
+/// Time after locking before an undelivered lock can be refunded (7 days)
+pub const REFUND_TIMEOUT_SECS: u64 = 604_800;
+
 // Symbol for referencing the BridgeStats struct
-const BRIDGE_STATS: Symbol = symbol_short!("B_STATS");
+pub(crate) const BRIDGE_STATS: Symbol = symbol_short!("B_STATS");
+
+/// Extend persistent entries once their TTL drops below ~1 day of ledgers
+const PERSISTENT_TTL_THRESHOLD: u32 = 17_280;
+
+/// Extend persistent entries to ~30 days of ledgers
+const PERSISTENT_TTL_EXTEND: u32 = 518_400;
+
+/// Write a persistent entry and extend its TTL
+///
+/// Per-transfer and per-proposal data goes to persistent storage so that the
+/// single instance entry does not grow with usage.
+pub(crate) fn set_persistent<K, V>(env: &Env, key: &K, value: &V)
+where
+    K: IntoVal<Env, Val>,
+    V: IntoVal<Env, Val>,
+{
+    env.storage().persistent().set(key, value);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, PERSISTENT_TTL_THRESHOLD, PERSISTENT_TTL_EXTEND);
+}
+
+/// Lifecycle of a lock
+///
+/// Pending -> Delivered -> Released (minted on NEAR, later burned back)
+/// Pending -> Cancelled -> Refunded (never delivered, returned to sender)
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransferStatus {
+    Pending,
+    Delivered,
+    Released,
+    Refunded,
+    Cancelled,
+}
+
+impl TransferStatus {
+    /// Whether moving from `self` to `next` is a valid transition
+    pub fn can_transition_to(self, next: TransferStatus) -> bool {
+        matches!(
+            (self, next),
+            (TransferStatus::Pending, TransferStatus::Delivered)
+                | (TransferStatus::Pending, TransferStatus::Cancelled)
+                | (TransferStatus::Delivered, TransferStatus::Released)
+                | (TransferStatus::Cancelled, TransferStatus::Refunded)
+        )
+    }
+}
 
 /// Structure for storing lock transaction details
 #[contracttype]
@@ -38,13 +140,15 @@ pub struct LockRecord {
     pub recipient_chain: u32,
     pub recipient: BytesN<32>,
     pub timestamp: u64,
-    pub is_released: bool,
+    pub status: TransferStatus,
+    /// Ledger timestamp of the last status change
+    pub status_updated_at: u64,
 }
 
 /// Mapping for lock records
 #[contracttype]
 pub enum LockBook {
-    Lock(u64), // Maps nonce to LockRecord
+    Lock(u64), // Maps nonce to LockRecord (persistent storage)
 }
 
 #[contract]
@@ -56,37 +160,63 @@ impl AuroraBridgeContract {
     /// 
     /// # Arguments
     /// * `admin` - Admin address for contract management
+    /// * `guardians` - Authorized guardian public keys (ed25519)
+    /// * `quorum` - Required number of guardian signatures
     /// 
     /// This function sets up the bridge contract with an admin and initializes counters
-    pub fn initialize(env: Env, admin: Address) {
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        guardians: Vec<BytesN<32>>,
+        quorum: u32,
+    ) -> Result<(), Error> {
         // Ensure not already initialized
         if env.storage().instance().has(&DataKey::Admin) {
             log!(&env, "Contract already initialized!");
-            panic!("Already initialized");
+            return Err(Error::AlreadyInitialized);
+        }
+
+        if quorum == 0 || quorum > guardians.len() {
+            return Err(Error::InvalidQuorum);
         }
 
         admin.require_auth();
 
-        // Store admin and initialize counters
+        // Store admin, guardian set and initialize counters
         env.storage().instance().set(&DataKey::Admin, &admin);
+        for role in [Role::Owner, Role::FeeManager, Role::TokenRegistrar] {
+            access::grant_role(&env, role, &admin);
+        }
+        env.storage().instance().set(&DataKey::Guardians, &guardians);
+        env.storage().instance().set(&DataKey::Quorum, &quorum);
         env.storage().instance().set(&DataKey::LockNonce, &0u64);
+        migration::set_version(&env, STORAGE_VERSION);
+        env.storage().instance().set(
+            &DataKey::Paused,
+            &PauseState {
+                inbound: false,
+                outbound: false,
+            },
+        );
         
         // Initialize bridge statistics
         let stats = BridgeStats {
             total_locked: 0,
             total_released: 0,
-            total_volume: 0,
         };
         env.storage().instance().set(&BRIDGE_STATS, &stats);
 
         env.storage().instance().extend_ttl(5000, 5000);
 
         log!(&env, "AuroraBridge initialized with admin: {}", admin);
+
+        Ok(())
     }
 
     /// Lock tokens for cross-chain transfer to NEAR
     /// 
     /// # Arguments
+    /// * `sender` - Address the tokens are taken from (and refunded to)
     /// * `token` - Token contract address to lock
     /// * `amount` - Amount to lock (in token's smallest unit)
     /// * `recipient_chain` - Destination chain ID (2 = NEAR)
@@ -96,134 +226,625 @@ impl AuroraBridgeContract {
     /// Unique nonce for tracking this transfer
     pub fn lock_tokens(
         env: Env,
+        sender: Address,
         token: Address,
         amount: i128,
         recipient_chain: u32,
         recipient: BytesN<32>,
-    ) -> u64 {
+    ) -> Result<u64, Error> {
+        if Self::view_pause_state(env.clone()).outbound {
+            return Err(Error::OutboundPaused);
+        }
+
         // Validate amount
         if amount <= 0 {
             log!(&env, "Amount must be positive!");
-            panic!("Amount must be positive");
+            return Err(Error::InvalidAmount);
         }
 
+        // Deduct the bridge fee; only the net amount is bridged
+        let fee = fees::compute_fee(&env, &token, amount)?;
+        let net_amount = amount - fee;
+
         // Get current nonce and increment
         let mut nonce: u64 = env.storage().instance().get(&DataKey::LockNonce).unwrap_or(0);
         nonce += 1;
 
         // Check if this nonce is already used (should not happen)
-        let existing_record = Self::view_lock_record(env.clone(), nonce);
-        if existing_record.is_released == false && existing_record.nonce != 0 {
+        if history::has_lock(&env, nonce) {
             log!(&env, "Lock already exists for this nonce!");
-            panic!("Lock already exists");
+            return Err(Error::LockAlreadyExists);
         }
 
+        sender.require_auth();
+
         // Get timestamp
         let timestamp = env.ledger().timestamp();
 
-        // Use contract address as sender (in production, use require_auth)
-        let sender = env.current_contract_address();
-
         // Create lock record
         let lock_record = LockRecord {
             nonce,
             token: token.clone(),
-            amount,
+            amount: net_amount,
             sender: sender.clone(),
             recipient_chain,
             recipient: recipient.clone(),
             timestamp,
-            is_released: false,
+            status: TransferStatus::Pending,
+            status_updated_at: timestamp,
         };
 
         // Store lock record
-        env.storage().instance().set(&LockBook::Lock(nonce), &lock_record);
+        history::put_lock(&env, &lock_record);
+        history::index_lock(&env, &sender, nonce);
 
         // Update nonce counter
         env.storage().instance().set(&DataKey::LockNonce, &nonce);
 
         // Update bridge statistics
         let mut stats = Self::view_bridge_stats(env.clone());
-        stats.total_locked += 1;
-        stats.total_volume += amount;
+        stats.total_locked = stats
+            .total_locked
+            .checked_add(1)
+            .ok_or(Error::ArithmeticOverflow)?;
         env.storage().instance().set(&BRIDGE_STATS, &stats);
+        stats::record_lock(&env, &token, net_amount, fee)?;
 
-        // Transfer tokens to contract (requires prior approval)
+        if fee > 0 {
+            fees::set_accrued(&env, &token, fees::accrued(&env, &token) + fee);
+        }
+
+        // Transfer tokens to contract (fee included)
         let token_client = token::Client::new(&env, &token);
         token_client.transfer(&sender, &env.current_contract_address(), &amount);
 
+        // Lock event for the relayer, which reads the ledger holding it
+        env.events()
+            .publish((symbol_short!("bridge"), symbol_short!("lock")), lock_record);
+
         // Extend storage TTL
         env.storage().instance().extend_ttl(5000, 5000);
 
         log!(
             &env,
-            "Tokens locked! Nonce: {}, Amount: {}, Recipient Chain: {}",
+            "Tokens locked! Nonce: {}, Amount: {}, Fee: {}, Recipient Chain: {}",
             nonce,
-            amount,
+            net_amount,
+            fee,
             recipient_chain
         );
 
-        nonce
+        Ok(nonce)
     }
 
     /// Release tokens after verification from NEAR chain
     /// 
     /// # Arguments
     /// * `nonce` - The lock nonce to release
+    /// * `burn_sequence` - Outbound sequence of the NEAR burn this settles
     /// * `recipient` - Address to receive the tokens
     /// 
     /// This function releases locked tokens to the specified recipient
     /// In production, this would be called by guardians after verifying NEAR transactions
-    pub fn release_tokens(env: Env, nonce: u64, recipient: Address) {
+    /// 
+    /// # Returns
+    /// `true` if released now, `false` if queued by the governor
+    pub fn release_tokens(
+        env: Env,
+        nonce: u64,
+        burn_sequence: u64,
+        recipient: Address,
+    ) -> Result<bool, Error> {
         // Get admin for authorization (in production, use guardian verification)
-        let admin: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::Admin)
-            .expect("Not initialized");
+        let admin = Self::admin(&env)?;
         admin.require_auth();
 
-        // Get lock record
-        let mut lock_record = Self::view_lock_record(env.clone(), nonce);
+        if Self::view_pause_state(env.clone()).inbound {
+            return Err(Error::InboundPaused);
+        }
+
+        // Get lock record and validate it can be released
+        let lock_record = Self::lock_record(&env, nonce)?;
+        Self::ensure_transition(&lock_record, TransferStatus::Released)?;
 
-        // Validate lock exists and hasn't been released
-        if lock_record.nonce == 0 {
-            log!(&env, "Lock record not found for nonce: {}", nonce);
-            panic!("Lock not found");
+        if governor::queued_release(&env, nonce).is_some() {
+            return Err(Error::ReleaseAlreadyQueued);
         }
+        let released_key = DataKey::BurnReleased(burn_sequence);
+        if env.storage().persistent().has(&released_key) {
+            return Err(Error::TransferAlreadyProcessed);
+        }
+        // Recorded for queued releases too; cancelling the release forgets it
+        set_persistent(&env, &released_key, &nonce);
+
+        // Large or over-limit releases wait out the governor delay
+        if governor::should_queue(&env, &lock_record.token, lock_record.amount) {
+            let queued = governor::queue_release(
+                &env,
+                nonce,
+                burn_sequence,
+                &recipient,
+                lock_record.amount,
+            );
+            env.storage().instance().extend_ttl(5000, 5000);
 
-        if lock_record.is_released {
-            log!(&env, "Tokens already released for nonce: {}", nonce);
-            panic!("Already released");
+            log!(
+                &env,
+                "Release queued by governor! Nonce: {}, Release after: {}",
+                nonce,
+                queued.release_after
+            );
+            return Ok(false);
         }
 
-        // Mark as released
-        lock_record.is_released = true;
-        env.storage().instance().set(&LockBook::Lock(nonce), &lock_record);
+        Self::complete_release(&env, lock_record, &recipient)?;
+        Ok(true)
+    }
 
-        // Update bridge statistics
-        let mut stats = Self::view_bridge_stats(env.clone());
-        stats.total_released += 1;
-        env.storage().instance().set(&BRIDGE_STATS, &stats);
+    /// Confirm a lock was delivered (minted) on NEAR
+    ///
+    /// # Arguments
+    /// * `nonce` - The lock nonce
+    /// * `signatures` - Guardian signatures attesting the delivery
+    ///
+    /// A delivered lock can be released but never refunded.
+    pub fn confirm_delivery(
+        env: Env,
+        nonce: u64,
+        signatures: Vec<GuardianSignature>,
+    ) -> Result<(), Error> {
+        let lock_record = Self::lock_record(&env, nonce)?;
+        Self::ensure_transition(&lock_record, TransferStatus::Delivered)?;
+
+        let digest = governance::attestation_digest(
+            &env,
+            &env.current_contract_address(),
+            nonce,
+            TransferStatus::Delivered,
+        );
+        governance::verify_guardian_signatures(&env, &digest, &signatures)?;
+
+        Self::set_status(&env, lock_record, TransferStatus::Delivered)?;
+        Ok(())
+    }
+
+    /// Cancel a lock that was never delivered to NEAR
+    ///
+    /// # Arguments
+    /// * `nonce` - The lock nonce to cancel
+    /// * `signatures` - Guardian signatures attesting the transfer was cancelled
+    ///
+    /// Only possible after `REFUND_TIMEOUT_SECS`; the sender can then `refund`.
+    pub fn cancel_lock(
+        env: Env,
+        nonce: u64,
+        signatures: Vec<GuardianSignature>,
+    ) -> Result<(), Error> {
+        let lock_record = Self::lock_record(&env, nonce)?;
+        Self::ensure_transition(&lock_record, TransferStatus::Cancelled)?;
+
+        if env.ledger().timestamp() < lock_record.timestamp + REFUND_TIMEOUT_SECS {
+            return Err(Error::RefundTimeoutNotReached);
+        }
+
+        let digest = governance::attestation_digest(
+            &env,
+            &env.current_contract_address(),
+            nonce,
+            TransferStatus::Cancelled,
+        );
+        governance::verify_guardian_signatures(&env, &digest, &signatures)?;
+
+        Self::set_status(&env, lock_record, TransferStatus::Cancelled)?;
+        Ok(())
+    }
+
+    /// Refund a cancelled lock to its original sender
+    ///
+    /// # Arguments
+    /// * `nonce` - The lock nonce to refund
+    pub fn refund(env: Env, nonce: u64) -> Result<(), Error> {
+        if Self::view_pause_state(env.clone()).inbound {
+            return Err(Error::InboundPaused);
+        }
+
+        let lock_record = Self::lock_record(&env, nonce)?;
+        Self::ensure_transition(&lock_record, TransferStatus::Refunded)?;
+
+        lock_record.sender.require_auth();
+
+        // Mark as refunded so the lock can never be released as well
+        let lock_record = Self::set_status(&env, lock_record, TransferStatus::Refunded)?;
+        stats::record_refund(&env, &lock_record.token, lock_record.amount)?;
 
-        // Transfer tokens to recipient
         let token_client = token::Client::new(&env, &lock_record.token);
         token_client.transfer(
             &env.current_contract_address(),
-            &recipient,
+            &lock_record.sender,
             &lock_record.amount,
         );
 
-        // Extend storage TTL
         env.storage().instance().extend_ttl(5000, 5000);
 
         log!(
             &env,
-            "Tokens released! Nonce: {}, Amount: {}, Recipient: {}",
+            "Tokens refunded! Nonce: {}, Amount: {}, Sender: {}",
             nonce,
             lock_record.amount,
-            recipient
+            lock_record.sender
+        );
+
+        Ok(())
+    }
+
+    /// Execute a release queued by the governor once its delay has passed
+    ///
+    /// # Arguments
+    /// * `nonce` - The lock nonce of the queued release
+    ///
+    /// Anyone may call this; the recipient was fixed when the release was queued.
+    pub fn execute_queued_release(env: Env, nonce: u64) -> Result<(), Error> {
+        if Self::view_pause_state(env.clone()).inbound {
+            return Err(Error::InboundPaused);
+        }
+
+        let queued = governor::queued_release(&env, nonce).ok_or(Error::ReleaseNotQueued)?;
+        if env.ledger().timestamp() < queued.release_after {
+            return Err(Error::ReleaseDelayed);
+        }
+
+        let lock_record = Self::lock_record(&env, nonce)?;
+        Self::ensure_transition(&lock_record, TransferStatus::Released)?;
+
+        governor::remove_queued_release(&env, nonce);
+        Self::complete_release(&env, lock_record, &queued.recipient)
+    }
+
+    /// Schedule new governor limits for a token (token registrar only)
+    ///
+    /// # Arguments
+    /// * `caller` - Token registrar address
+    /// * `token` - Token contract address
+    /// * `daily_limit` - Maximum amount released per rolling 24h window
+    /// * `big_tx_threshold` - Releases of at least this amount are always queued
+    ///
+    /// # Returns
+    /// The timelock id; run `execute_timelocked` after TIMELOCK_DELAY_SECS
+    pub fn set_token_limit(
+        env: Env,
+        caller: Address,
+        token: Address,
+        daily_limit: i128,
+        big_tx_threshold: i128,
+    ) -> Result<u64, Error> {
+        access::require_role(&env, &caller, Role::TokenRegistrar)?;
+
+        if daily_limit <= 0 || big_tx_threshold <= 0 {
+            return Err(Error::InvalidLimit);
+        }
+
+        Ok(Self::schedule(
+            &env,
+            TimelockAction::SetTokenLimit(token, daily_limit, big_tx_threshold),
+        ))
+    }
+
+    /// Register the wrapped token for a foreign asset (token registrar only)
+    ///
+    /// # Arguments
+    /// * `caller` - Token registrar address
+    /// * `meta` - Asset metadata attested by the guardians
+    /// * `signatures` - Guardian signatures over the AssetMeta digest
+    /// * `token` - Stellar asset contract whose admin is this bridge
+    pub fn create_wrapped_asset(
+        env: Env,
+        caller: Address,
+        meta: AssetMeta,
+        signatures: Vec<GuardianSignature>,
+        token: Address,
+    ) -> Result<(), Error> {
+        access::require_role(&env, &caller, Role::TokenRegistrar)?;
+
+        if wrapped::wrapped_asset(&env, meta.token_chain, &meta.token_address).is_some()
+            || wrapped::asset_meta(&env, &token).is_some()
+        {
+            return Err(Error::WrappedAssetExists);
+        }
+
+        let digest = wrapped::asset_meta_digest(&env, &env.current_contract_address(), &meta);
+        governance::verify_guardian_signatures(&env, &digest, &signatures)?;
+
+        // The bridge must be able to mint the wrapped token
+        if StellarAssetClient::new(&env, &token).admin() != env.current_contract_address() {
+            return Err(Error::NotTokenAdmin);
+        }
+
+        wrapped::register_wrapped_asset(&env, &meta, &token);
+        env.storage().instance().extend_ttl(5000, 5000);
+
+        log!(
+            &env,
+            "Wrapped asset created! Token: {}, Chain: {}, Symbol: {}",
+            token,
+            meta.token_chain,
+            meta.symbol
+        );
+
+        Ok(())
+    }
+
+    /// Mint wrapped tokens for a guardian-attested inbound transfer
+    ///
+    /// # Arguments
+    /// * `transfer` - Inbound transfer of a foreign asset
+    /// * `signatures` - Guardian signatures over the WrappedTransfer digest
+    pub fn mint_wrapped(
+        env: Env,
+        transfer: WrappedTransfer,
+        signatures: Vec<GuardianSignature>,
+    ) -> Result<(), Error> {
+        if Self::view_pause_state(env.clone()).inbound {
+            return Err(Error::InboundPaused);
+        }
+
+        if transfer.amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let token = wrapped::wrapped_asset(&env, transfer.token_chain, &transfer.token_address)
+            .ok_or(Error::WrappedAssetNotFound)?;
+
+        if wrapped::is_minted(&env, transfer.token_chain, transfer.nonce) {
+            return Err(Error::TransferAlreadyProcessed);
+        }
+
+        let digest =
+            wrapped::wrapped_transfer_digest(&env, &env.current_contract_address(), &transfer);
+        governance::verify_guardian_signatures(&env, &digest, &signatures)?;
+
+        wrapped::mark_minted(&env, transfer.token_chain, transfer.nonce);
+
+        StellarAssetClient::new(&env, &token).mint(&transfer.recipient, &transfer.amount);
+
+        env.storage().instance().extend_ttl(5000, 5000);
+
+        log!(
+            &env,
+            "Wrapped tokens minted! Source nonce: {}, Amount: {}, Recipient: {}",
+            transfer.nonce,
+            transfer.amount,
+            transfer.recipient
+        );
+
+        Ok(())
+    }
+
+    /// Burn wrapped tokens to unlock the asset on its native chain
+    ///
+    /// # Arguments
+    /// * `sender` - Holder of the wrapped tokens
+    /// * `token` - Wrapped token contract address
+    /// * `amount` - Amount to burn
+    /// * `recipient` - Recipient on the asset's native chain
+    ///
+    /// # Returns
+    /// The burn nonce
+    pub fn burn_wrapped(
+        env: Env,
+        sender: Address,
+        token: Address,
+        amount: i128,
+        recipient: BytesN<32>,
+    ) -> Result<u64, Error> {
+        if Self::view_pause_state(env.clone()).outbound {
+            return Err(Error::OutboundPaused);
+        }
+
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let meta = wrapped::asset_meta(&env, &token).ok_or(Error::WrappedAssetNotFound)?;
+
+        sender.require_auth();
+
+        token::Client::new(&env, &token).burn(&sender, &amount);
+
+        let record =
+            wrapped::record_burn(&env, &token, amount, &sender, meta.token_chain, &recipient);
+        env.storage().instance().extend_ttl(5000, 5000);
+
+        log!(
+            &env,
+            "Wrapped tokens burned! Nonce: {}, Amount: {}, Recipient Chain: {}",
+            record.nonce,
+            amount,
+            meta.token_chain
         );
+
+        Ok(record.nonce)
+    }
+
+    /// View the wrapped token for a foreign asset
+    pub fn view_wrapped_asset(
+        env: Env,
+        token_chain: u32,
+        token_address: BytesN<32>,
+    ) -> Option<Address> {
+        wrapped::wrapped_asset(&env, token_chain, &token_address)
+    }
+
+    /// View the foreign asset metadata of a wrapped token
+    pub fn view_asset_meta(env: Env, token: Address) -> Option<AssetMeta> {
+        wrapped::asset_meta(&env, &token)
+    }
+
+    /// View a burn of wrapped tokens by burn nonce
+    pub fn view_burn_record(env: Env, nonce: u64) -> Option<BurnRecord> {
+        wrapped::burn_record(&env, nonce)
+    }
+
+    /// Check whether an inbound VAA was processed
+    ///
+    /// # Arguments
+    /// * `source_chain` - Chain the transfer came from
+    /// * `nonce` - Sequence of the transfer on that chain
+    pub fn is_vaa_processed(env: Env, source_chain: u32, nonce: u64) -> bool {
+        wrapped::is_minted(&env, source_chain, nonce)
+    }
+
+    /// Check whether a NEAR burn was released (or queued for release)
+    ///
+    /// # Arguments
+    /// * `burn_sequence` - Outbound sequence of the burn on NEAR
+    pub fn is_burn_released(env: Env, burn_sequence: u64) -> bool {
+        env.storage()
+            .persistent()
+            .has(&DataKey::BurnReleased(burn_sequence))
+    }
+
+    /// Schedule a new bridge fee for a token (fee manager only)
+    ///
+    /// # Arguments
+    /// * `caller` - Fee manager address
+    /// * `token` - Token contract address
+    /// * `bps` - Proportional fee in basis points (at most 10_000)
+    /// * `min_fee` - Minimum fee in token units
+    ///
+    /// # Returns
+    /// The timelock id; run `execute_timelocked` after TIMELOCK_DELAY_SECS
+    pub fn set_fee(
+        env: Env,
+        caller: Address,
+        token: Address,
+        bps: u32,
+        min_fee: i128,
+    ) -> Result<u64, Error> {
+        access::require_role(&env, &caller, Role::FeeManager)?;
+
+        if bps as i128 > fees::BPS_DENOMINATOR || min_fee < 0 {
+            return Err(Error::InvalidFee);
+        }
+
+        Ok(Self::schedule(&env, TimelockAction::SetFee(token, bps, min_fee)))
+    }
+
+    /// Execute a scheduled parameter change once its delay has passed
+    ///
+    /// # Arguments
+    /// * `id` - Timelock id
+    ///
+    /// Anyone may execute; guardians can cancel before then with a
+    /// `GovernanceAction::CancelTimelock` VAA.
+    pub fn execute_timelocked(env: Env, id: u64) -> Result<(), Error> {
+        let entry = timelock::entry(&env, id).ok_or(Error::TimelockNotFound)?;
+        if env.ledger().timestamp() < entry.eta {
+            return Err(Error::TimelockNotReady);
+        }
+
+        timelock::remove(&env, id);
+
+        match entry.action {
+            TimelockAction::UpdateGuardians(guardians, quorum) => {
+                if quorum == 0 || quorum > guardians.len() {
+                    return Err(Error::InvalidQuorum);
+                }
+                env.storage().instance().set(&DataKey::Guardians, &guardians);
+                env.storage().instance().set(&DataKey::Quorum, &quorum);
+                log!(
+                    &env,
+                    "Guardians updated! Count: {}, Quorum: {}",
+                    guardians.len(),
+                    quorum
+                );
+            }
+            TimelockAction::SetFee(token, bps, min_fee) => {
+                fees::set_fee_config(&env, &token, &FeeConfig { bps, min_fee });
+                log!(
+                    &env,
+                    "Fee set! Token: {}, Bps: {}, Min fee: {}",
+                    token,
+                    bps,
+                    min_fee
+                );
+            }
+            TimelockAction::SetTokenLimit(token, daily_limit, big_tx_threshold) => {
+                governor::set_token_limit(
+                    &env,
+                    &token,
+                    &TokenLimit {
+                        daily_limit,
+                        big_tx_threshold,
+                    },
+                );
+                log!(
+                    &env,
+                    "Token limit set! Token: {}, Daily limit: {}, Big tx threshold: {}",
+                    token,
+                    daily_limit,
+                    big_tx_threshold
+                );
+            }
+        }
+
+        env.storage().instance().extend_ttl(5000, 5000);
+
+        Ok(())
+    }
+
+    /// View a scheduled parameter change
+    pub fn view_timelocked(env: Env, id: u64) -> Option<TimelockEntry> {
+        timelock::entry(&env, id)
+    }
+
+    /// Withdraw all accrued fees of a token to the treasury (fee manager only)
+    ///
+    /// # Arguments
+    /// * `caller` - Fee manager address
+    /// * `token` - Token contract address
+    ///
+    /// # Returns
+    /// The amount withdrawn
+    pub fn withdraw_fees(env: Env, caller: Address, token: Address) -> Result<i128, Error> {
+        access::require_role(&env, &caller, Role::FeeManager)?;
+
+        let treasury = fees::treasury(&env).ok_or(Error::NoTreasury)?;
+        let amount = fees::accrued(&env, &token);
+        if amount == 0 {
+            return Ok(0);
+        }
+
+        fees::set_accrued(&env, &token, 0);
+
+        let token_client = token::Client::new(&env, &token);
+        token_client.transfer(&env.current_contract_address(), &treasury, &amount);
+
+        env.storage().instance().extend_ttl(5000, 5000);
+
+        log!(
+            &env,
+            "Fees withdrawn! Token: {}, Amount: {}, Treasury: {}",
+            token,
+            amount,
+            treasury
+        );
+
+        Ok(amount)
+    }
+
+    /// View the fee configuration of a token
+    pub fn view_fee_config(env: Env, token: Address) -> Option<FeeConfig> {
+        fees::fee_config(&env, &token)
+    }
+
+    /// View fees accrued for a token and not yet withdrawn
+    pub fn view_accrued_fees(env: Env, token: Address) -> i128 {
+        fees::accrued(&env, &token)
+    }
+
+    /// View the treasury address
+    pub fn view_treasury(env: Env) -> Option<Address> {
+        fees::treasury(&env)
     }
 
     /// View details of a specific lock transaction by nonce
@@ -232,90 +853,450 @@ impl AuroraBridgeContract {
     /// * `nonce` - The lock nonce to query
     /// 
     /// # Returns
-    /// LockRecord with transaction details or default values if not found
-    pub fn view_lock_record(env: Env, nonce: u64) -> LockRecord {
-        let key = LockBook::Lock(nonce);
-
-        env.storage().instance().get(&key).unwrap_or(LockRecord {
-            // Return default values if not found
-            nonce: 0,
-            token: env.current_contract_address(),
-            amount: 0,
-            sender: env.current_contract_address(),
-            recipient_chain: 0,
-            recipient: BytesN::from_array(&env, &[0u8; 32]),
-            timestamp: 0,
-            is_released: false,
-        })
+    /// LockRecord with transaction details, or None if not found
+    pub fn view_lock_record(env: Env, nonce: u64) -> Option<LockRecord> {
+        history::get_lock(&env, nonce)
+    }
+
+    /// View per-token statistics
+    ///
+    /// # Arguments
+    /// * `token` - Token contract address
+    ///
+    /// # Returns
+    /// TokenStats with locked, released, refunded, outstanding and fee amounts
+    pub fn view_token_stats(env: Env, token: Address) -> TokenStats {
+        stats::token_stats(&env, &token)
+    }
+
+    /// List locks in nonce order
+    ///
+    /// # Arguments
+    /// * `from_nonce` - First nonce to include
+    /// * `limit` - Maximum number of records (capped at MAX_PAGE_SIZE)
+    ///
+    /// # Returns
+    /// LockRecords; continue from the last nonce + 1
+    pub fn list_locks(env: Env, from_nonce: u64, limit: u32) -> Vec<LockRecord> {
+        history::list_locks(&env, from_nonce, limit)
+    }
+
+    /// List a sender's locks, oldest first
+    ///
+    /// # Arguments
+    /// * `sender` - Address that locked the tokens
+    /// * `cursor` - Position in the sender's history to start at (0 = first lock)
+    /// * `limit` - Maximum number of records (capped at MAX_PAGE_SIZE)
+    ///
+    /// # Returns
+    /// LockRecords; continue from `cursor` + number returned
+    pub fn locks_by_sender(
+        env: Env,
+        sender: Address,
+        cursor: u32,
+        limit: u32,
+    ) -> Vec<LockRecord> {
+        history::locks_by_sender(&env, &sender, cursor, limit)
+    }
+
+    /// View the number of locks made by a sender
+    pub fn view_sender_lock_count(env: Env, sender: Address) -> u32 {
+        history::sender_lock_count(&env, &sender)
     }
 
     /// View overall bridge statistics
     /// 
     /// # Returns
-    /// BridgeStats with total locks, releases, and volume
+    /// BridgeStats with total lock and release counts
     pub fn view_bridge_stats(env: Env) -> BridgeStats {
         env.storage().instance().get(&BRIDGE_STATS).unwrap_or(BridgeStats {
             total_locked: 0,
             total_released: 0,
-            total_volume: 0,
         })
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use soroban_sdk::{testutils::Address as _, Env};
+    /// Pause inbound and/or outbound transfers
+    ///
+    /// # Arguments
+    /// * `caller` - Pauser or owner address
+    /// * `inbound` - Pause `release_tokens`
+    /// * `outbound` - Pause `lock_tokens`
+    ///
+    /// Switches that are already paused stay paused; unpausing requires an
+    /// `AdminAction::Unpause` proposal or a governance VAA.
+    pub fn pause(env: Env, caller: Address, inbound: bool, outbound: bool) -> Result<(), Error> {
+        caller.require_auth();
+
+        if !access::has_role(&env, Role::Pauser, &caller)
+            && !access::has_role(&env, Role::Owner, &caller)
+        {
+            return Err(Error::NotAuthorized);
+        }
 
-    #[test]
-    fn test_initialize() {
-        let env = Env::default();
-        let contract_id = env.register_contract(None, AuroraBridgeContract);
-        let client = AuroraBridgeContractClient::new(&env, &contract_id);
+        let mut state = Self::stored_pause_state(&env);
+        state.inbound |= inbound;
+        state.outbound |= outbound;
+        Self::set_pause_state(&env, &state);
 
-        let admin = Address::generate(&env);
+        Ok(())
+    }
 
-        env.mock_all_auths();
-        client.initialize(&admin);
+    /// Execute a guardian-signed governance VAA
+    ///
+    /// # Arguments
+    /// * `vaa` - Governance VAA meeting the guardian quorum
+    ///
+    /// Anyone may submit; authority comes from the guardian signatures.
+    pub fn submit_governance(env: Env, vaa: GovernanceVaa) -> Result<(), Error> {
+        match governance::consume_governance_vaa(&env, &vaa)? {
+            GovernanceAction::SetPause(inbound, outbound) => {
+                Self::set_pause_state(&env, &PauseState { inbound, outbound });
+            }
+            GovernanceAction::CancelQueuedRelease(nonce) => {
+                let queued =
+                    governor::queued_release(&env, nonce).ok_or(Error::ReleaseNotQueued)?;
+                governor::remove_queued_release(&env, nonce);
+                env.storage()
+                    .persistent()
+                    .remove(&DataKey::BurnReleased(queued.burn_sequence));
+                log!(&env, "Queued release cancelled! Nonce: {}", nonce);
+            }
+            GovernanceAction::Upgrade(new_wasm_hash) => {
+                Self::upgrade_wasm(&env, new_wasm_hash);
+            }
+            GovernanceAction::CancelTimelock(id) => {
+                if timelock::entry(&env, id).is_none() {
+                    return Err(Error::TimelockNotFound);
+                }
+                timelock::remove(&env, id);
 
-        let stats = client.view_bridge_stats();
-        assert_eq!(stats.total_locked, 0);
-        assert_eq!(stats.total_released, 0);
+                log!(&env, "Timelocked action cancelled! Id: {}", id);
+            }
+        }
+
+        log!(&env, "Governance VAA executed! Sequence: {}", vaa.sequence);
+
+        Ok(())
     }
 
-    #[test]
-    #[should_panic(expected = "Already initialized")]
-    fn test_double_initialize() {
-        let env = Env::default();
-        let contract_id = env.register_contract(None, AuroraBridgeContract);
-        let client = AuroraBridgeContractClient::new(&env, &contract_id);
+    /// Run pending storage migrations (admin only)
+    ///
+    /// Call after an `AdminAction::Upgrade` whose code bumps `STORAGE_VERSION`.
+    /// Steps that walk every lock handle `MIGRATION_BATCH` locks per call, so
+    /// call again until the result is `STORAGE_VERSION`. Transfers are
+    /// rejected as paused until then (see `view_pause_state`).
+    ///
+    /// # Returns
+    /// The storage version after migrating
+    pub fn migrate(env: Env) -> Result<u32, Error> {
+        Self::admin(&env)?.require_auth();
 
-        let admin = Address::generate(&env);
+        let version = migration::run(&env)?;
+        env.storage().instance().extend_ttl(5000, 5000);
 
-        env.mock_all_auths();
-        client.initialize(&admin);
-        client.initialize(&admin); // Should panic
+        Ok(version)
     }
 
-    #[test]
-    fn test_lock_tokens() {
-        let env = Env::default();
-        let contract_id = env.register_contract(None, AuroraBridgeContract);
-        let client = AuroraBridgeContractClient::new(&env, &contract_id);
+    /// Propose a sensitive admin action (owner only)
+    ///
+    /// # Arguments
+    /// * `proposer` - Owner address; counts as the first approval
+    /// * `action` - Action to execute once approved
+    ///
+    /// # Returns
+    /// The proposal id
+    pub fn propose(env: Env, proposer: Address, action: AdminAction) -> Result<u64, Error> {
+        access::require_role(&env, &proposer, Role::Owner)?;
 
-        let admin = Address::generate(&env);
-        let token_address = Address::generate(&env);
-        let recipient = BytesN::from_array(&env, &[1u8; 32]);
+        let proposal = access::create_proposal(&env, &proposer, action);
+        env.storage().instance().extend_ttl(5000, 5000);
 
-        env.mock_all_auths();
-        client.initialize(&admin);
+        log!(&env, "Proposal created! Id: {}, Proposer: {}", proposal.id, proposer);
 
-        let nonce = client.lock_tokens(&token_address, &1000000, &2, &recipient);
-        assert_eq!(nonce, 1);
+        Ok(proposal.id)
+    }
+
+    /// Approve a pending proposal (owner only)
+    ///
+    /// # Arguments
+    /// * `owner` - Approving owner address
+    /// * `id` - Proposal id
+    pub fn approve(env: Env, owner: Address, id: u64) -> Result<(), Error> {
+        access::require_role(&env, &owner, Role::Owner)?;
+
+        let mut proposal = access::pending_proposal(&env, id)?;
+        if proposal.approvals.contains(&owner) {
+            return Err(Error::AlreadyApproved);
+        }
+
+        proposal.approvals.push_back(owner.clone());
+        access::set_proposal(&env, &proposal);
+        env.storage().instance().extend_ttl(5000, 5000);
+
+        log!(&env, "Proposal approved! Id: {}, Owner: {}", id, owner);
+
+        Ok(())
+    }
+
+    /// Execute a proposal approved by at least `threshold` current owners
+    ///
+    /// # Arguments
+    /// * `id` - Proposal id
+    ///
+    /// Anyone may execute; authority comes from the recorded approvals.
+    pub fn execute_proposal(env: Env, id: u64) -> Result<(), Error> {
+        let proposal = access::pending_proposal(&env, id)?;
+        if access::current_approvals(&env, &proposal) < access::threshold(&env) {
+            return Err(Error::ThresholdNotReached);
+        }
+
+        access::remove_proposal(&env, id);
+
+        match proposal.action {
+            AdminAction::GrantRole(role, account) => access::grant_role(&env, role, &account),
+            AdminAction::RevokeRole(role, account) => access::revoke_role(&env, role, &account)?,
+            AdminAction::SetThreshold(threshold) => access::set_threshold(&env, threshold)?,
+            AdminAction::TransferAdmin(new_admin) => {
+                env.storage().instance().set(&DataKey::PendingAdmin, &new_admin);
+                log!(&env, "Admin transfer proposed to: {}", new_admin);
+            }
+            AdminAction::Upgrade(new_wasm_hash) => Self::upgrade_wasm(&env, new_wasm_hash),
+            AdminAction::UpdateGuardians(guardians, quorum) => {
+                if quorum == 0 || quorum > guardians.len() {
+                    return Err(Error::InvalidQuorum);
+                }
+                Self::schedule(&env, TimelockAction::UpdateGuardians(guardians, quorum));
+            }
+            AdminAction::SetTreasury(treasury) => {
+                fees::set_treasury(&env, &treasury);
+                log!(&env, "Treasury set to: {}", treasury);
+            }
+            AdminAction::Unpause(inbound, outbound) => {
+                let mut state = Self::stored_pause_state(&env);
+                state.inbound &= !inbound;
+                state.outbound &= !outbound;
+                Self::set_pause_state(&env, &state);
+            }
+        }
+
+        env.storage().instance().extend_ttl(5000, 5000);
+
+        log!(&env, "Proposal executed! Id: {}", id);
+
+        Ok(())
+    }
+
+    /// View a proposal awaiting execution by id
+    pub fn view_proposal(env: Env, id: u64) -> Option<Proposal> {
+        access::proposal(&env, id)
+    }
+
+    /// View the members of a role
+    pub fn view_role_members(env: Env, role: Role) -> Vec<Address> {
+        access::members(&env, role)
+    }
 
-        let lock_record = client.view_lock_record(&nonce);
-        assert_eq!(lock_record.amount, 1000000);
-        assert_eq!(lock_record.recipient_chain, 2);
-        assert_eq!(lock_record.is_released, false);
+    /// View the number of owner approvals required to execute a proposal
+    pub fn view_threshold(env: Env) -> u32 {
+        access::threshold(&env)
+    }
+
+    /// Accept a proposed admin transfer (pending admin only)
+    pub fn accept_admin(env: Env) -> Result<(), Error> {
+        let new_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingAdmin)
+            .ok_or(Error::NoPendingAdmin)?;
+        new_admin.require_auth();
+
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+        env.storage().instance().extend_ttl(5000, 5000);
+
+        log!(&env, "Admin transferred to: {}", new_admin);
+
+        Ok(())
+    }
+
+    /// View the current admin
+    pub fn view_admin(env: Env) -> Result<Address, Error> {
+        Self::admin(&env)
+    }
+
+    /// View the proposed admin, if any
+    pub fn view_pending_admin(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::PendingAdmin)
+    }
+
+    /// View the stored storage layout version
+    pub fn view_storage_version(env: Env) -> u32 {
+        migration::stored_version(&env)
+    }
+
+    /// View current pause switches
+    ///
+    /// Both directions read as paused while a storage migration is pending,
+    /// so no transfer changes the records a migration step is walking.
+    pub fn view_pause_state(env: Env) -> PauseState {
+        if migration::stored_version(&env) < STORAGE_VERSION {
+            return PauseState {
+                inbound: true,
+                outbound: true,
+            };
+        }
+        Self::stored_pause_state(&env)
+    }
+
+    /// View the guardian set and quorum
+    pub fn view_guardians(env: Env) -> (Vec<BytesN<32>>, u32) {
+        let guardians = env
+            .storage()
+            .instance()
+            .get(&DataKey::Guardians)
+            .unwrap_or(Vec::new(&env));
+        let quorum = env.storage().instance().get(&DataKey::Quorum).unwrap_or(0);
+        (guardians, quorum)
+    }
+
+    /// View governor limits for a token
+    pub fn view_token_limit(env: Env, token: Address) -> Option<TokenLimit> {
+        governor::token_limit(&env, &token)
+    }
+
+    /// View the amount of a token released in the current 24h window
+    pub fn view_window_usage(env: Env, token: Address) -> i128 {
+        governor::window_usage(&env, &token)
+    }
+
+    /// View a release held back by the governor
+    pub fn view_queued_release(env: Env, nonce: u64) -> Option<QueuedRelease> {
+        governor::queued_release(&env, nonce)
+    }
+
+    /// Mark a lock as released and transfer its tokens to `recipient`
+    fn complete_release(
+        env: &Env,
+        lock_record: LockRecord,
+        recipient: &Address,
+    ) -> Result<(), Error> {
+        let nonce = lock_record.nonce;
+
+        // Mark as released
+        let lock_record = Self::set_status(env, lock_record, TransferStatus::Released)?;
+
+        // Update bridge statistics
+        let mut stats = Self::view_bridge_stats(env.clone());
+        stats.total_released = stats
+            .total_released
+            .checked_add(1)
+            .ok_or(Error::ArithmeticOverflow)?;
+        env.storage().instance().set(&BRIDGE_STATS, &stats);
+        stats::record_release(env, &lock_record.token, lock_record.amount)?;
+
+        governor::record_flow(env, &lock_record.token, lock_record.amount);
+
+        // Transfer tokens to recipient
+        let token_client = token::Client::new(env, &lock_record.token);
+        token_client.transfer(
+            &env.current_contract_address(),
+            recipient,
+            &lock_record.amount,
+        );
+
+        // Extend storage TTL
+        env.storage().instance().extend_ttl(5000, 5000);
+
+        log!(
+            env,
+            "Tokens released! Nonce: {}, Amount: {}, Recipient: {}",
+            nonce,
+            lock_record.amount,
+            recipient
+        );
+
+        Ok(())
+    }
+
+    fn lock_record(env: &Env, nonce: u64) -> Result<LockRecord, Error> {
+        Self::view_lock_record(env.clone(), nonce).ok_or_else(|| {
+            log!(env, "Lock record not found for nonce: {}", nonce);
+            Error::LockNotFound
+        })
+    }
+
+    fn ensure_transition(lock_record: &LockRecord, next: TransferStatus) -> Result<(), Error> {
+        if !lock_record.status.can_transition_to(next) {
+            return Err(Error::InvalidStatusTransition);
+        }
+        Ok(())
+    }
+
+    /// Move a lock to `next`, enforcing the transition rules
+    fn set_status(
+        env: &Env,
+        mut lock_record: LockRecord,
+        next: TransferStatus,
+    ) -> Result<LockRecord, Error> {
+        Self::ensure_transition(&lock_record, next)?;
+
+        lock_record.status = next;
+        lock_record.status_updated_at = env.ledger().timestamp();
+        history::put_lock(env, &lock_record);
+
+        log!(
+            env,
+            "Lock status updated! Nonce: {}, Status: {}",
+            lock_record.nonce,
+            next
+        );
+
+        Ok(lock_record)
+    }
+
+    fn admin(env: &Env) -> Result<Address, Error> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)
+    }
+
+    fn schedule(env: &Env, action: TimelockAction) -> u64 {
+        let entry = timelock::schedule(env, action);
+        env.storage().instance().extend_ttl(5000, 5000);
+
+        log!(env, "Action scheduled! Id: {}, Eta: {}", entry.id, entry.eta);
+
+        entry.id
+    }
+
+    fn upgrade_wasm(env: &Env, new_wasm_hash: BytesN<32>) {
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+
+        log!(env, "Contract upgraded to WASM: {}", new_wasm_hash);
+    }
+
+    /// Pause switches as set by the pauser, owners and governance
+    fn stored_pause_state(env: &Env) -> PauseState {
+        env.storage().instance().get(&DataKey::Paused).unwrap_or(PauseState {
+            inbound: false,
+            outbound: false,
+        })
+    }
+
+    fn set_pause_state(env: &Env, state: &PauseState) {
+        env.storage().instance().set(&DataKey::Paused, state);
+        env.storage().instance().extend_ttl(5000, 5000);
+
+        log!(
+            env,
+            "Pause state updated! Inbound: {}, Outbound: {}",
+            state.inbound,
+            state.outbound
+        );
     }
 }
+
+#[cfg(test)]
+mod test;
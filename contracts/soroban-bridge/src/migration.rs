@@ -0,0 +1,199 @@
+use soroban_sdk::{contracttype, log, Address, BytesN, Env};
+
+use crate::{
+    access, history, stats, BridgeStats, DataKey, Error, LockBook, LockRecord, Role,
+    TransferStatus, BRIDGE_STATS,
+};
+
+/// Storage layout version written by this contract code
+///
+/// Bump this and add a step to `migrate_step` whenever the storage layout
+/// changes, then call `migrate` after `upgrade`.
+pub const STORAGE_VERSION: u32 = 1;
+
+/// Lock nonces visited per `migrate` call, across all steps
+///
+/// Steps that walk the locks stop after this many and resume from a stored
+/// cursor on the next call. The v1 step writes four persistent entries per
+/// lock, which keeps a call well within the transaction limits.
+pub const MIGRATION_BATCH: u64 = 10;
+
+/// Progress of a step that walks the locks
+#[contracttype]
+#[derive(Clone)]
+pub(crate) struct MigrationCursor {
+    /// Next lock nonce to visit
+    pub next: u64,
+    /// Last lock nonce issued when the step started
+    pub end: u64,
+}
+
+/// Storage layout version currently stored (0 for pre-versioning deployments)
+pub fn stored_version(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::StorageVersion)
+        .unwrap_or(0)
+}
+
+pub fn set_version(env: &Env, version: u32) {
+    env.storage()
+        .instance()
+        .set(&DataKey::StorageVersion, &version);
+}
+
+/// Run migration steps between the stored version and `STORAGE_VERSION`
+///
+/// Stops early once `MIGRATION_BATCH` locks have been visited.
+///
+/// # Returns
+/// The storage version after migrating
+pub fn run(env: &Env) -> Result<u32, Error> {
+    let mut version = stored_version(env);
+    if version > STORAGE_VERSION {
+        return Err(Error::UnsupportedStorageVersion);
+    }
+
+    let mut budget = MIGRATION_BATCH;
+    while version < STORAGE_VERSION {
+        if !migrate_step(env, version + 1, &mut budget)? {
+            log!(env, "Storage migration to version {} paused", version + 1);
+            return Ok(version);
+        }
+        version += 1;
+        set_version(env, version);
+
+        log!(env, "Storage migrated to version: {}", version);
+    }
+
+    Ok(version)
+}
+
+/// LockRecord as stored before versioning (in instance storage)
+#[contracttype]
+pub(crate) struct LockRecordV0 {
+    pub nonce: u64,
+    pub token: Address,
+    pub amount: i128,
+    pub sender: Address,
+    pub recipient_chain: u32,
+    pub recipient: BytesN<32>,
+    pub timestamp: u64,
+    pub is_released: bool,
+}
+
+/// BridgeStats as stored before versioning (mixed-token volume total)
+#[contracttype]
+pub(crate) struct BridgeStatsV0 {
+    pub total_locked: u64,
+    pub total_released: u64,
+    pub total_volume: i128,
+}
+
+/// Call `visit` for each lock nonce from the stored cursor on, within `budget`
+///
+/// Nonces issued after the step started are not visited; they were written
+/// in the new layout. Returns `true` once every nonce has been visited.
+fn walk_locks(
+    env: &Env,
+    budget: &mut u64,
+    mut visit: impl FnMut(u64) -> Result<(), Error>,
+) -> Result<bool, Error> {
+    let mut cursor: MigrationCursor = env
+        .storage()
+        .instance()
+        .get(&DataKey::MigrationCursor)
+        .unwrap_or_else(|| MigrationCursor {
+            next: 1,
+            end: env
+                .storage()
+                .instance()
+                .get(&DataKey::LockNonce)
+                .unwrap_or(0),
+        });
+
+    while cursor.next <= cursor.end {
+        if *budget == 0 {
+            env.storage()
+                .instance()
+                .set(&DataKey::MigrationCursor, &cursor);
+            return Ok(false);
+        }
+        visit(cursor.next)?;
+        cursor.next += 1;
+        *budget -= 1;
+    }
+
+    env.storage().instance().remove(&DataKey::MigrationCursor);
+    Ok(true)
+}
+
+/// Migrate storage from `version - 1` to `version`
+///
+/// Returns `false` if the step ran out of `budget` and must be called again.
+fn migrate_step(env: &Env, version: u32, budget: &mut u64) -> Result<bool, Error> {
+    match version {
+        // v1: from the layout before versioning. Amount totals move from
+        // BridgeStats to per-token TokenStats and the admin becomes the sole
+        // owner (threshold 1) with the fee and token roles. Lock records
+        // trade `is_released` for `status` and move from instance to
+        // persistent storage, indexed by sender and counted into TokenStats
+        // (paged). Nothing was refundable yet; the time of a release was
+        // never stored, so `status_updated_at` is the lock time.
+        1 => {
+            if !env.storage().instance().has(&DataKey::MigrationCursor) {
+                let old: Option<BridgeStatsV0> = env.storage().instance().get(&BRIDGE_STATS);
+                if let Some(old) = old {
+                    let stats = BridgeStats {
+                        total_locked: old.total_locked,
+                        total_released: old.total_released,
+                    };
+                    env.storage().instance().set(&BRIDGE_STATS, &stats);
+                }
+
+                let admin: Address = env
+                    .storage()
+                    .instance()
+                    .get(&DataKey::Admin)
+                    .ok_or(Error::NotInitialized)?;
+                for role in [Role::Owner, Role::FeeManager, Role::TokenRegistrar] {
+                    access::grant_role(env, role, &admin);
+                }
+            }
+
+            walk_locks(env, budget, |nonce| {
+                let key = LockBook::Lock(nonce);
+                let old: Option<LockRecordV0> = env.storage().instance().get(&key);
+                let Some(old) = old else {
+                    return Ok(());
+                };
+
+                let record = LockRecord {
+                    nonce: old.nonce,
+                    token: old.token,
+                    amount: old.amount,
+                    sender: old.sender,
+                    recipient_chain: old.recipient_chain,
+                    recipient: old.recipient,
+                    timestamp: old.timestamp,
+                    status: if old.is_released {
+                        TransferStatus::Released
+                    } else {
+                        TransferStatus::Pending
+                    },
+                    status_updated_at: old.timestamp,
+                };
+                history::put_lock(env, &record);
+                history::index_lock(env, &record.sender, nonce);
+                env.storage().instance().remove(&key);
+
+                stats::record_lock(env, &record.token, record.amount, 0)?;
+                if old.is_released {
+                    stats::record_release(env, &record.token, record.amount)?;
+                }
+                Ok(())
+            })
+        }
+        _ => unreachable!(),
+    }
+}
@@ -0,0 +1,87 @@
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::{set_persistent, Error};
+
+/// Per-token bridge statistics
+///
+/// Amounts are in the token's own units. `outstanding` is what the bridge
+/// must still hold in escrow: locked - released - refunded. Together with
+/// unwithdrawn fees it should equal the bridge's token balance.
+#[contracttype]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TokenStats {
+    /// Net amount locked (Stellar -> NEAR)
+    pub locked: i128,
+    /// Amount released back to Stellar (NEAR -> Stellar)
+    pub released: i128,
+    /// Amount refunded from cancelled locks
+    pub refunded: i128,
+    /// Amount still escrowed for outstanding transfers
+    pub outstanding: i128,
+    /// Fees collected on locks; locks from before storage v4 count as zero
+    pub fees: i128,
+    pub lock_count: u64,
+    pub release_count: u64,
+}
+
+/// Storage keys for statistics
+#[contracttype]
+pub enum StatsKey {
+    /// Token -> TokenStats (persistent)
+    Token(Address),
+}
+
+pub fn token_stats(env: &Env, token: &Address) -> TokenStats {
+    env.storage()
+        .persistent()
+        .get(&StatsKey::Token(token.clone()))
+        .unwrap_or_default()
+}
+
+fn set_token_stats(env: &Env, token: &Address, stats: &TokenStats) {
+    set_persistent(env, &StatsKey::Token(token.clone()), stats);
+}
+
+fn add(a: i128, b: i128) -> Result<i128, Error> {
+    a.checked_add(b).ok_or(Error::ArithmeticOverflow)
+}
+
+fn sub(a: i128, b: i128) -> Result<i128, Error> {
+    a.checked_sub(b).ok_or(Error::ArithmeticOverflow)
+}
+
+/// Record a lock of `amount` (net of `fee`)
+pub fn record_lock(env: &Env, token: &Address, amount: i128, fee: i128) -> Result<(), Error> {
+    let mut stats = token_stats(env, token);
+    stats.locked = add(stats.locked, amount)?;
+    stats.outstanding = add(stats.outstanding, amount)?;
+    stats.fees = add(stats.fees, fee)?;
+    stats.lock_count = stats
+        .lock_count
+        .checked_add(1)
+        .ok_or(Error::ArithmeticOverflow)?;
+    set_token_stats(env, token, &stats);
+    Ok(())
+}
+
+/// Record a release of `amount`
+pub fn record_release(env: &Env, token: &Address, amount: i128) -> Result<(), Error> {
+    let mut stats = token_stats(env, token);
+    stats.released = add(stats.released, amount)?;
+    stats.outstanding = sub(stats.outstanding, amount)?;
+    stats.release_count = stats
+        .release_count
+        .checked_add(1)
+        .ok_or(Error::ArithmeticOverflow)?;
+    set_token_stats(env, token, &stats);
+    Ok(())
+}
+
+/// Record a refund of `amount`
+pub fn record_refund(env: &Env, token: &Address, amount: i128) -> Result<(), Error> {
+    let mut stats = token_stats(env, token);
+    stats.refunded = add(stats.refunded, amount)?;
+    stats.outstanding = sub(stats.outstanding, amount)?;
+    set_token_stats(env, token, &stats);
+    Ok(())
+}
@@ -0,0 +1,1501 @@
+extern crate std;
+
+use super::*;
+use crate::governance::{attestation_digest, governance_digest};
+use crate::history::HistoryKey;
+use crate::migration::{BridgeStatsV0, LockRecordV0};
+use crate::wrapped::{asset_meta_digest, wrapped_transfer_digest};
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{
+    testutils::{Address as _, Events, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    vec, Env, IntoVal, String, TryFromVal,
+};
+
+fn guardian_keys() -> std::vec::Vec<SigningKey> {
+    (1u8..=3)
+        .map(|i| SigningKey::from_bytes(&[i; 32]))
+        .collect()
+}
+
+fn guardian_pubkeys(env: &Env, keys: &[SigningKey]) -> Vec<BytesN<32>> {
+    let mut pubkeys = Vec::new(env);
+    for key in keys {
+        pubkeys.push_back(BytesN::from_array(env, &key.verifying_key().to_bytes()));
+    }
+    pubkeys
+}
+
+fn sign(env: &Env, keys: &[SigningKey], digest: &BytesN<32>) -> Vec<GuardianSignature> {
+    let mut signatures = Vec::new(env);
+    for key in keys {
+        let signature = key.sign(&digest.to_array());
+        signatures.push_back(GuardianSignature {
+            guardian_pubkey: BytesN::from_array(env, &key.verifying_key().to_bytes()),
+            signature: BytesN::from_array(env, &signature.to_bytes()),
+        });
+    }
+    signatures
+}
+
+fn governance_vaa(
+    env: &Env,
+    contract_id: &Address,
+    keys: &[SigningKey],
+    sequence: u64,
+    action: GovernanceAction,
+) -> GovernanceVaa {
+    let digest = governance_digest(env, contract_id, sequence, &action);
+    GovernanceVaa {
+        sequence,
+        action,
+        signatures: sign(env, keys, &digest),
+    }
+}
+
+/// Register and initialize the bridge with 3 guardians and a quorum of 2
+fn setup(env: &Env) -> (AuroraBridgeContractClient<'_>, Address) {
+    let contract_id = env.register(AuroraBridgeContract, ());
+    let client = AuroraBridgeContractClient::new(env, &contract_id);
+
+    let admin = Address::generate(env);
+
+    env.mock_all_auths();
+    client.initialize(&admin, &guardian_pubkeys(env, &guardian_keys()), &2);
+
+    (client, admin)
+}
+
+/// Propose and execute an admin action as the sole owner (threshold 1)
+fn run_admin_action(client: &AuroraBridgeContractClient, owner: &Address, action: AdminAction) {
+    let id = client.propose(owner, &action);
+    client.execute_proposal(&id);
+}
+
+/// Let the timelock delay pass and execute timelocked action `id`
+fn apply_timelocked(env: &Env, client: &AuroraBridgeContractClient, id: u64) {
+    env.ledger()
+        .with_mut(|l| l.timestamp += TIMELOCK_DELAY_SECS);
+    client.execute_timelocked(&id);
+}
+
+fn set_token_limit_now(
+    env: &Env,
+    client: &AuroraBridgeContractClient,
+    caller: &Address,
+    token: &Address,
+    daily_limit: &i128,
+    big_tx_threshold: &i128,
+) {
+    let id = client.set_token_limit(caller, token, daily_limit, big_tx_threshold);
+    apply_timelocked(env, client, id);
+}
+
+fn set_fee_now(
+    env: &Env,
+    client: &AuroraBridgeContractClient,
+    caller: &Address,
+    token: &Address,
+    bps: &u32,
+    min_fee: &i128,
+) {
+    let id = client.set_fee(caller, token, bps, min_fee);
+    apply_timelocked(env, client, id);
+}
+
+/// Create a Stellar asset and fund the bridge with it
+fn create_token(env: &Env, holder: &Address, amount: i128) -> Address {
+    let issuer = Address::generate(env);
+    let token = env.register_stellar_asset_contract_v2(issuer).address();
+    StellarAssetClient::new(env, &token).mint(holder, &amount);
+    token
+}
+
+/// Fund a new sender with `amount` of a new token and lock it
+fn lock_new_token(env: &Env, client: &AuroraBridgeContractClient, amount: i128) -> (Address, u64) {
+    let sender = Address::generate(env);
+    let token_address = create_token(env, &sender, amount);
+    let recipient = BytesN::from_array(env, &[1u8; 32]);
+    let nonce = client.lock_tokens(&sender, &token_address, &amount, &2, &recipient);
+    (token_address, nonce)
+}
+
+/// Attest (with a guardian quorum) that lock `nonce` reached `status`
+fn attest(
+    env: &Env,
+    client: &AuroraBridgeContractClient,
+    nonce: u64,
+    status: TransferStatus,
+) -> Vec<GuardianSignature> {
+    let digest = attestation_digest(env, &client.address, nonce, status);
+    sign(env, &guardian_keys()[..2], &digest)
+}
+
+/// Confirm delivery of lock `nonce` so it can be released
+fn deliver(env: &Env, client: &AuroraBridgeContractClient, nonce: u64) {
+    client.confirm_delivery(
+        &nonce,
+        &attest(env, client, nonce, TransferStatus::Delivered),
+    );
+}
+
+#[test]
+fn test_initialize() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+
+    let stats = client.view_bridge_stats();
+    assert_eq!(stats.total_locked, 0);
+    assert_eq!(stats.total_released, 0);
+
+    let (guardians, quorum) = client.view_guardians();
+    assert_eq!(guardians.len(), 3);
+    assert_eq!(quorum, 2);
+}
+
+#[test]
+fn test_double_initialize() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+
+    assert_eq!(
+        client.try_initialize(&admin, &guardian_pubkeys(&env, &guardian_keys()), &2),
+        Err(Ok(Error::AlreadyInitialized))
+    );
+}
+
+#[test]
+fn test_initialize_invalid_quorum() {
+    let env = Env::default();
+    let contract_id = env.register(AuroraBridgeContract, ());
+    let client = AuroraBridgeContractClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    assert_eq!(
+        client.try_initialize(
+            &Address::generate(&env),
+            &guardian_pubkeys(&env, &guardian_keys()),
+            &4,
+        ),
+        Err(Ok(Error::InvalidQuorum))
+    );
+}
+
+#[test]
+fn test_lock_tokens() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+
+    let sender = Address::generate(&env);
+    let token_address = create_token(&env, &sender, 1000000);
+    let recipient = BytesN::from_array(&env, &[1u8; 32]);
+
+    let nonce = client.lock_tokens(&sender, &token_address, &1000000, &2, &recipient);
+    assert_eq!(nonce, 1);
+    let lock_event = env.events().all().iter().find_map(|(contract, topics, data)| {
+        let topics_match = topics
+            == vec![
+                &env,
+                symbol_short!("bridge").into_val(&env),
+                symbol_short!("lock").into_val(&env),
+            ];
+        (contract == client.address && topics_match)
+            .then(|| LockRecord::try_from_val(&env, &data).unwrap())
+    });
+    let lock_event = lock_event.unwrap();
+    assert_eq!(lock_event.nonce, nonce);
+    assert_eq!(lock_event.token, token_address);
+    assert_eq!(lock_event.recipient, recipient);
+    assert_eq!(
+        TokenClient::new(&env, &token_address).balance(&client.address),
+        1000000
+    );
+
+    let lock_record = client.view_lock_record(&nonce).unwrap();
+    assert_eq!(lock_record.amount, 1000000);
+    assert_eq!(lock_record.recipient_chain, 2);
+    assert_eq!(lock_record.status, TransferStatus::Pending);
+}
+
+#[test]
+fn test_pauser_blocks_lock_tokens() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+
+    let pauser = Address::generate(&env);
+    run_admin_action(
+        &client,
+        &admin,
+        AdminAction::GrantRole(Role::Pauser, pauser.clone()),
+    );
+    client.pause(&pauser, &false, &true);
+
+    assert_eq!(
+        client.view_pause_state(),
+        PauseState {
+            inbound: false,
+            outbound: true
+        }
+    );
+
+    let sender = Address::generate(&env);
+    let token_address = create_token(&env, &sender, 1000);
+    let recipient = BytesN::from_array(&env, &[1u8; 32]);
+    assert_eq!(
+        client.try_lock_tokens(&sender, &token_address, &1000, &2, &recipient),
+        Err(Ok(Error::OutboundPaused))
+    );
+}
+
+#[test]
+fn test_pause_blocks_release_tokens() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+
+    let (_token_address, nonce) = lock_new_token(&env, &client, 1000);
+    deliver(&env, &client, nonce);
+
+    client.pause(&admin, &true, &false);
+    assert_eq!(
+        client.try_release_tokens(&nonce, &nonce, &Address::generate(&env)),
+        Err(Ok(Error::InboundPaused))
+    );
+}
+
+#[test]
+fn test_pause_requires_role() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+
+    assert_eq!(
+        client.try_pause(&Address::generate(&env), &true, &true),
+        Err(Ok(Error::NotAuthorized))
+    );
+}
+
+#[test]
+fn test_admin_unpause() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+
+    client.pause(&admin, &true, &true);
+    run_admin_action(&client, &admin, AdminAction::Unpause(false, true));
+
+    assert_eq!(
+        client.view_pause_state(),
+        PauseState {
+            inbound: true,
+            outbound: false
+        }
+    );
+}
+
+#[test]
+fn test_governance_pause_and_unpause() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let keys = guardian_keys();
+
+    let vaa = governance_vaa(
+        &env,
+        &client.address,
+        &keys[..2],
+        1,
+        GovernanceAction::SetPause(true, true),
+    );
+    client.submit_governance(&vaa);
+    assert!(client.view_pause_state().inbound);
+
+    let vaa = governance_vaa(
+        &env,
+        &client.address,
+        &keys[1..],
+        2,
+        GovernanceAction::SetPause(false, false),
+    );
+    client.submit_governance(&vaa);
+    assert!(!client.view_pause_state().inbound);
+    assert!(!client.view_pause_state().outbound);
+}
+
+#[test]
+fn test_governance_below_quorum() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let keys = guardian_keys();
+
+    let mut vaa = governance_vaa(
+        &env,
+        &client.address,
+        &keys[..1],
+        1,
+        GovernanceAction::SetPause(true, true),
+    );
+    // Duplicate signatures from the same guardian do not count twice
+    let duplicate = vaa.signatures.get(0).unwrap();
+    vaa.signatures.push_back(duplicate);
+
+    assert_eq!(
+        client.try_submit_governance(&vaa),
+        Err(Ok(Error::InsufficientSignatures))
+    );
+}
+
+#[test]
+fn test_governance_replay() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let keys = guardian_keys();
+
+    let vaa = governance_vaa(
+        &env,
+        &client.address,
+        &keys,
+        1,
+        GovernanceAction::SetPause(true, false),
+    );
+    client.submit_governance(&vaa);
+    assert_eq!(
+        client.try_submit_governance(&vaa),
+        Err(Ok(Error::GovernanceReplay))
+    );
+}
+
+#[test]
+fn test_release_within_limit() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+
+    let (token_address, nonce) = lock_new_token(&env, &client, 1000);
+    deliver(&env, &client, nonce);
+    set_token_limit_now(&env, &client, &admin, &token_address, &5000, &2000);
+
+    let recipient = Address::generate(&env);
+    assert!(client.release_tokens(&nonce, &nonce, &recipient));
+    assert_eq!(
+        TokenClient::new(&env, &token_address).balance(&recipient),
+        1000
+    );
+    assert_eq!(client.view_window_usage(&token_address), 1000);
+}
+
+#[test]
+fn test_burn_sequence_is_released_once() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+
+    let (_, first) = lock_new_token(&env, &client, 1000);
+    let (_, second) = lock_new_token(&env, &client, 1000);
+    deliver(&env, &client, first);
+    deliver(&env, &client, second);
+
+    assert!(!client.is_burn_released(&7));
+    client.release_tokens(&first, &7, &Address::generate(&env));
+    assert!(client.is_burn_released(&7));
+    assert!(!client.is_burn_released(&first));
+
+    assert_eq!(
+        client.try_release_tokens(&second, &7, &Address::generate(&env)),
+        Err(Ok(Error::TransferAlreadyProcessed))
+    );
+}
+
+#[test]
+fn test_big_release_is_queued() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+
+    let (token_address, nonce) = lock_new_token(&env, &client, 3000);
+    deliver(&env, &client, nonce);
+    set_token_limit_now(&env, &client, &admin, &token_address, &5000, &2000);
+
+    let recipient = Address::generate(&env);
+    assert!(!client.release_tokens(&nonce, &nonce, &recipient));
+    assert_eq!(
+        TokenClient::new(&env, &token_address).balance(&recipient),
+        0
+    );
+
+    let queued = client.view_queued_release(&nonce).unwrap();
+    assert_eq!(queued.amount, 3000);
+
+    env.ledger()
+        .with_mut(|ledger| ledger.timestamp = queued.release_after);
+    client.execute_queued_release(&nonce);
+
+    assert_eq!(
+        TokenClient::new(&env, &token_address).balance(&recipient),
+        3000
+    );
+    assert_eq!(
+        client.view_lock_record(&nonce).unwrap().status,
+        TransferStatus::Released
+    );
+    assert!(client.view_queued_release(&nonce).is_none());
+}
+
+#[test]
+fn test_queued_release_before_delay() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+
+    let (token_address, nonce) = lock_new_token(&env, &client, 3000);
+    deliver(&env, &client, nonce);
+    set_token_limit_now(&env, &client, &admin, &token_address, &5000, &2000);
+
+    client.release_tokens(&nonce, &nonce, &Address::generate(&env));
+    assert_eq!(
+        client.try_execute_queued_release(&nonce),
+        Err(Ok(Error::ReleaseDelayed))
+    );
+}
+
+#[test]
+fn test_daily_limit_rolls_over() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+
+    let sender = Address::generate(&env);
+    let token_address = create_token(&env, &sender, 3000);
+    set_token_limit_now(&env, &client, &admin, &token_address, &2500, &2000);
+
+    let recipient = BytesN::from_array(&env, &[1u8; 32]);
+    let first = client.lock_tokens(&sender, &token_address, &1500, &2, &recipient);
+    let second = client.lock_tokens(&sender, &token_address, &1500, &2, &recipient);
+
+    deliver(&env, &client, first);
+    deliver(&env, &client, second);
+    assert!(client.release_tokens(&first, &first, &Address::generate(&env)));
+    // Would exceed the 24h limit
+    assert!(!client.release_tokens(&second, &second, &Address::generate(&env)));
+
+    env.ledger()
+        .with_mut(|ledger| ledger.timestamp += governor::WINDOW_SECS);
+    assert_eq!(client.view_window_usage(&token_address), 0);
+}
+
+#[test]
+fn test_flows_in_the_same_hour_share_an_entry() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+
+    let sender = Address::generate(&env);
+    let token_address = create_token(&env, &sender, 300);
+    set_token_limit_now(&env, &client, &admin, &token_address, &10_000, &5_000);
+
+    let recipient = BytesN::from_array(&env, &[1u8; 32]);
+    for _ in 0..3 {
+        let nonce = client.lock_tokens(&sender, &token_address, &100, &2, &recipient);
+        deliver(&env, &client, nonce);
+        assert!(client.release_tokens(&nonce, &nonce, &Address::generate(&env)));
+        env.ledger().with_mut(|ledger| ledger.timestamp += 60);
+    }
+    assert_eq!(client.view_window_usage(&token_address), 300);
+
+    env.as_contract(&client.address, || {
+        let key = governor::GovernorKey::Flows(token_address.clone());
+        let flows: Vec<governor::Flow> = env.storage().persistent().get(&key).unwrap();
+        assert_eq!(flows.len(), 1);
+        assert!(!env.storage().instance().has(&key));
+    });
+}
+
+#[test]
+fn test_governance_cancels_queued_release() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let keys = guardian_keys();
+
+    let (token_address, nonce) = lock_new_token(&env, &client, 3000);
+    deliver(&env, &client, nonce);
+    set_token_limit_now(&env, &client, &admin, &token_address, &5000, &2000);
+    client.release_tokens(&nonce, &nonce, &Address::generate(&env));
+
+    let vaa = governance_vaa(
+        &env,
+        &client.address,
+        &keys,
+        1,
+        GovernanceAction::CancelQueuedRelease(nonce),
+    );
+    assert!(client.is_burn_released(&nonce));
+    client.submit_governance(&vaa);
+    assert!(!client.is_burn_released(&nonce));
+    assert_eq!(
+        client.view_lock_record(&nonce).unwrap().status,
+        TransferStatus::Delivered
+    );
+
+    env.ledger()
+        .with_mut(|ledger| ledger.timestamp += governor::QUEUE_DELAY_SECS);
+    assert_eq!(
+        client.try_execute_queued_release(&nonce),
+        Err(Ok(Error::ReleaseNotQueued))
+    );
+}
+
+#[test]
+fn test_view_missing_lock_record() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+
+    assert!(client.view_lock_record(&42).is_none());
+}
+
+#[test]
+fn test_refund_after_timeout() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+
+    let (token_address, nonce) = lock_new_token(&env, &client, 1000);
+    let sender = client.view_lock_record(&nonce).unwrap().sender;
+
+    env.ledger()
+        .with_mut(|ledger| ledger.timestamp += REFUND_TIMEOUT_SECS);
+    client.cancel_lock(
+        &nonce,
+        &attest(&env, &client, nonce, TransferStatus::Cancelled),
+    );
+    client.refund(&nonce);
+
+    assert_eq!(
+        TokenClient::new(&env, &token_address).balance(&sender),
+        1000
+    );
+
+    let lock_record = client.view_lock_record(&nonce).unwrap();
+    assert_eq!(lock_record.status, TransferStatus::Refunded);
+    assert_eq!(lock_record.status_updated_at, env.ledger().timestamp());
+}
+
+#[test]
+fn test_cancel_before_timeout() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+
+    let (_token_address, nonce) = lock_new_token(&env, &client, 1000);
+
+    assert_eq!(
+        client.try_cancel_lock(
+            &nonce,
+            &attest(&env, &client, nonce, TransferStatus::Cancelled),
+        ),
+        Err(Ok(Error::RefundTimeoutNotReached))
+    );
+}
+
+#[test]
+fn test_cancel_requires_quorum() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+
+    let (_token_address, nonce) = lock_new_token(&env, &client, 1000);
+
+    env.ledger()
+        .with_mut(|ledger| ledger.timestamp += REFUND_TIMEOUT_SECS);
+    let digest = attestation_digest(&env, &client.address, nonce, TransferStatus::Cancelled);
+    assert_eq!(
+        client.try_cancel_lock(&nonce, &sign(&env, &guardian_keys()[..1], &digest)),
+        Err(Ok(Error::InsufficientSignatures))
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Crypto, InvalidInput)")]
+fn test_delivery_attestation_cannot_cancel() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+
+    let (_token_address, nonce) = lock_new_token(&env, &client, 1000);
+
+    env.ledger()
+        .with_mut(|ledger| ledger.timestamp += REFUND_TIMEOUT_SECS);
+    client.cancel_lock(
+        &nonce,
+        &attest(&env, &client, nonce, TransferStatus::Delivered),
+    );
+}
+
+#[test]
+fn test_pending_lock_cannot_be_refunded() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+
+    let (_token_address, nonce) = lock_new_token(&env, &client, 1000);
+
+    env.ledger()
+        .with_mut(|ledger| ledger.timestamp += REFUND_TIMEOUT_SECS);
+    assert_eq!(
+        client.try_refund(&nonce),
+        Err(Ok(Error::InvalidStatusTransition))
+    );
+}
+
+#[test]
+fn test_delivered_lock_cannot_be_cancelled() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+
+    let (_token_address, nonce) = lock_new_token(&env, &client, 1000);
+    deliver(&env, &client, nonce);
+
+    env.ledger()
+        .with_mut(|ledger| ledger.timestamp += REFUND_TIMEOUT_SECS);
+    assert_eq!(
+        client.try_cancel_lock(
+            &nonce,
+            &attest(&env, &client, nonce, TransferStatus::Cancelled),
+        ),
+        Err(Ok(Error::InvalidStatusTransition))
+    );
+}
+
+#[test]
+fn test_refunded_lock_cannot_be_released() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+
+    let (_token_address, nonce) = lock_new_token(&env, &client, 1000);
+
+    env.ledger()
+        .with_mut(|ledger| ledger.timestamp += REFUND_TIMEOUT_SECS);
+    client.cancel_lock(
+        &nonce,
+        &attest(&env, &client, nonce, TransferStatus::Cancelled),
+    );
+    client.refund(&nonce);
+
+    assert_eq!(
+        client.try_release_tokens(&nonce, &nonce, &Address::generate(&env)),
+        Err(Ok(Error::InvalidStatusTransition))
+    );
+}
+
+#[test]
+fn test_admin_transfer() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+
+    let new_admin = Address::generate(&env);
+    run_admin_action(
+        &client,
+        &admin,
+        AdminAction::TransferAdmin(new_admin.clone()),
+    );
+    assert_eq!(client.view_admin(), admin);
+    assert_eq!(client.view_pending_admin(), Some(new_admin.clone()));
+
+    client.accept_admin();
+    assert_eq!(client.view_admin(), new_admin);
+    assert_eq!(client.view_pending_admin(), None);
+}
+
+#[test]
+fn test_accept_admin_without_proposal() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+
+    assert_eq!(client.try_accept_admin(), Err(Ok(Error::NoPendingAdmin)));
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_accept_admin_requires_new_admin() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+
+    run_admin_action(
+        &client,
+        &admin,
+        AdminAction::TransferAdmin(Address::generate(&env)),
+    );
+
+    env.set_auths(&[]);
+    client.accept_admin();
+}
+
+#[test]
+fn test_upgrade_requires_owner_threshold() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+
+    let owner = Address::generate(&env);
+    run_admin_action(
+        &client,
+        &admin,
+        AdminAction::GrantRole(Role::Owner, owner.clone()),
+    );
+    run_admin_action(&client, &admin, AdminAction::SetThreshold(2));
+
+    let id = client.propose(
+        &admin,
+        &AdminAction::Upgrade(BytesN::from_array(&env, &[0u8; 32])),
+    );
+    assert_eq!(
+        client.try_execute_proposal(&id),
+        Err(Ok(Error::ThresholdNotReached))
+    );
+}
+
+/// Rewrite the contract's storage in the layout from before versioning:
+/// locks in instance storage with a released flag, mixed-token totals in
+/// BridgeStats, no sender index, token stats or roles
+fn simulate_unversioned(env: &Env, client: &AuroraBridgeContractClient, released: &[u64]) {
+    env.as_contract(&client.address, || {
+        let end: u64 = env.storage().instance().get(&DataKey::LockNonce).unwrap();
+        let mut total_volume = 0;
+        for nonce in 1..=end {
+            let record: LockRecord = env
+                .storage()
+                .persistent()
+                .get(&LockBook::Lock(nonce))
+                .unwrap();
+            total_volume += record.amount;
+            env.storage().persistent().remove(&LockBook::Lock(nonce));
+            env.storage()
+                .persistent()
+                .remove(&HistoryKey::SenderCount(record.sender.clone()));
+            env.storage()
+                .persistent()
+                .remove(&HistoryKey::SenderLock(record.sender.clone(), 0));
+            env.storage()
+                .persistent()
+                .remove(&crate::stats::StatsKey::Token(record.token.clone()));
+
+            let old = LockRecordV0 {
+                nonce,
+                token: record.token,
+                amount: record.amount,
+                sender: record.sender,
+                recipient_chain: record.recipient_chain,
+                recipient: record.recipient,
+                timestamp: record.timestamp,
+                is_released: released.contains(&nonce),
+            };
+            env.storage().instance().set(&LockBook::Lock(nonce), &old);
+        }
+
+        env.storage().instance().set(
+            &BRIDGE_STATS,
+            &BridgeStatsV0 {
+                total_locked: end,
+                total_released: released.len() as u64,
+                total_volume,
+            },
+        );
+        for role in [Role::Owner, Role::FeeManager, Role::TokenRegistrar] {
+            env.storage()
+                .instance()
+                .remove(&crate::access::AccessKey::Members(role));
+        }
+        env.storage().instance().remove(&DataKey::StorageVersion);
+    });
+}
+
+#[test]
+fn test_migrate_from_unversioned_storage() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    assert_eq!(client.view_storage_version(), STORAGE_VERSION);
+    let (token, released) = lock_new_token(&env, &client, 100);
+    deliver(&env, &client, released);
+    client.release_tokens(&released, &released, &Address::generate(&env));
+    let (other_token, pending) = lock_new_token(&env, &client, 40);
+    let sender = client.view_lock_record(&pending).unwrap().sender;
+
+    simulate_unversioned(&env, &client, &[released]);
+    assert_eq!(client.view_storage_version(), 0);
+    assert!(client.view_lock_record(&pending).is_none());
+
+    assert_eq!(client.migrate(), STORAGE_VERSION);
+    assert_eq!(client.view_storage_version(), STORAGE_VERSION);
+
+    let stats = client.view_bridge_stats();
+    assert_eq!(stats.total_locked, 2);
+    assert_eq!(stats.total_released, 1);
+
+    let status = |nonce| client.view_lock_record(&nonce).unwrap().status;
+    assert_eq!(status(released), TransferStatus::Released);
+    assert_eq!(status(pending), TransferStatus::Pending);
+    assert_eq!(client.locks_by_sender(&sender, &0, &10).len(), 1);
+    env.as_contract(&client.address, || {
+        assert!(!env.storage().instance().has(&LockBook::Lock(pending)));
+    });
+
+    let token_stats = client.view_token_stats(&token);
+    assert_eq!(token_stats.locked, 100);
+    assert_eq!(token_stats.released, 100);
+    assert_eq!(token_stats.outstanding, 0);
+    assert_eq!(token_stats.release_count, 1);
+    assert_eq!(client.view_token_stats(&other_token).outstanding, 40);
+
+    for role in [Role::Owner, Role::FeeManager, Role::TokenRegistrar] {
+        assert_eq!(client.view_role_members(&role), vec![&env, admin.clone()]);
+    }
+
+    // Already current: nothing to do
+    assert_eq!(client.migrate(), STORAGE_VERSION);
+}
+
+#[test]
+fn test_migrate_pages_through_locks() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let count = MIGRATION_BATCH + 2;
+    let locks: std::vec::Vec<(Address, u64)> = (0..count)
+        .map(|_| lock_new_token(&env, &client, 10))
+        .collect();
+    simulate_unversioned(&env, &client, &[]);
+
+    // The first call stops part way through the locks
+    assert_eq!(client.migrate(), 0);
+    assert!(client.view_lock_record(&1).is_some());
+    assert!(client.view_lock_record(&count).is_none());
+
+    // Transfers wait for the migration to finish
+    let (token, nonce) = &locks[0];
+    assert_eq!(
+        client.try_release_tokens(nonce, nonce, &Address::generate(&env)),
+        Err(Ok(Error::InboundPaused))
+    );
+    let sender = Address::generate(&env);
+    assert_eq!(
+        client.try_lock_tokens(&sender, token, &10, &2, &BytesN::from_array(&env, &[1u8; 32])),
+        Err(Ok(Error::OutboundPaused))
+    );
+
+    assert_eq!(client.migrate(), STORAGE_VERSION);
+
+    for (token, nonce) in &locks {
+        assert_eq!(client.view_lock_record(nonce).unwrap().amount, 10);
+        assert_eq!(client.view_token_stats(token).outstanding, 10);
+    }
+    env.as_contract(&client.address, || {
+        assert!(!env.storage().instance().has(&DataKey::MigrationCursor));
+    });
+}
+
+fn near_asset_meta(env: &Env) -> AssetMeta {
+    AssetMeta {
+        token_chain: 2,
+        token_address: BytesN::from_array(env, &[7u8; 32]),
+        decimals: 7,
+        symbol: String::from_str(env, "wNEAR"),
+        name: String::from_str(env, "Wrapped NEAR"),
+    }
+}
+
+/// Create a SAC administered by the bridge and register it for `meta`
+fn create_wrapped(env: &Env, client: &AuroraBridgeContractClient, meta: &AssetMeta) -> Address {
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(env))
+        .address();
+    StellarAssetClient::new(env, &token).set_admin(&client.address);
+
+    let digest = asset_meta_digest(env, &client.address, meta);
+    let signatures = sign(env, &guardian_keys()[..2], &digest);
+    client.create_wrapped_asset(&client.view_admin(), meta, &signatures, &token);
+    token
+}
+
+fn wrapped_transfer(env: &Env, meta: &AssetMeta, nonce: u64, amount: i128) -> WrappedTransfer {
+    WrappedTransfer {
+        token_chain: meta.token_chain,
+        token_address: meta.token_address.clone(),
+        nonce,
+        recipient: Address::generate(env),
+        amount,
+    }
+}
+
+#[test]
+fn test_wrapped_mint_and_burn() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let meta = near_asset_meta(&env);
+    let token = create_wrapped(&env, &client, &meta);
+
+    assert_eq!(
+        client.view_wrapped_asset(&meta.token_chain, &meta.token_address),
+        Some(token.clone())
+    );
+    assert_eq!(client.view_asset_meta(&token), Some(meta.clone()));
+
+    let transfer = wrapped_transfer(&env, &meta, 1, 500);
+    let digest = wrapped_transfer_digest(&env, &client.address, &transfer);
+    client.mint_wrapped(&transfer, &sign(&env, &guardian_keys()[..2], &digest));
+
+    let token_client = TokenClient::new(&env, &token);
+    assert_eq!(token_client.balance(&transfer.recipient), 500);
+
+    let recipient = BytesN::from_array(&env, &[9u8; 32]);
+    let nonce = client.burn_wrapped(&transfer.recipient, &token, &200, &recipient);
+    assert_eq!(token_client.balance(&transfer.recipient), 300);
+
+    let burn = client.view_burn_record(&nonce).unwrap();
+    assert_eq!(burn.amount, 200);
+    assert_eq!(burn.recipient_chain, meta.token_chain);
+    assert_eq!(burn.recipient, recipient);
+}
+
+#[test]
+fn test_wrapped_mint_replay() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let meta = near_asset_meta(&env);
+    create_wrapped(&env, &client, &meta);
+
+    let transfer = wrapped_transfer(&env, &meta, 1, 500);
+    let digest = wrapped_transfer_digest(&env, &client.address, &transfer);
+    let signatures = sign(&env, &guardian_keys()[..2], &digest);
+    client.mint_wrapped(&transfer, &signatures);
+
+    assert_eq!(
+        client.try_mint_wrapped(&transfer, &signatures),
+        Err(Ok(Error::TransferAlreadyProcessed))
+    );
+}
+
+#[test]
+fn test_wrapped_mint_nonces_are_per_source_chain() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let meta = near_asset_meta(&env);
+    create_wrapped(&env, &client, &meta);
+    let other_meta = AssetMeta {
+        token_chain: 3,
+        ..near_asset_meta(&env)
+    };
+    create_wrapped(&env, &client, &other_meta);
+
+    for meta in [&meta, &other_meta] {
+        let transfer = wrapped_transfer(&env, meta, 1, 500);
+        let digest = wrapped_transfer_digest(&env, &client.address, &transfer);
+        client.mint_wrapped(&transfer, &sign(&env, &guardian_keys()[..2], &digest));
+    }
+    assert!(client.is_vaa_processed(&meta.token_chain, &1));
+    assert!(!client.is_vaa_processed(&meta.token_chain, &2));
+    assert!(!client.is_vaa_processed(&1, &1));
+
+    env.as_contract(&client.address, || {
+        let key = wrapped::WrappedKey::Minted(meta.token_chain, 1);
+        assert!(env.storage().persistent().has(&key));
+        assert!(!env.storage().instance().has(&key));
+    });
+}
+
+#[test]
+fn test_wrapped_mint_requires_quorum() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let meta = near_asset_meta(&env);
+    create_wrapped(&env, &client, &meta);
+
+    let transfer = wrapped_transfer(&env, &meta, 1, 500);
+    let digest = wrapped_transfer_digest(&env, &client.address, &transfer);
+
+    assert_eq!(
+        client.try_mint_wrapped(&transfer, &sign(&env, &guardian_keys()[..1], &digest)),
+        Err(Ok(Error::InsufficientSignatures))
+    );
+}
+
+#[test]
+fn test_wrapped_mint_unknown_asset() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let meta = near_asset_meta(&env);
+
+    let transfer = wrapped_transfer(&env, &meta, 1, 500);
+    let digest = wrapped_transfer_digest(&env, &client.address, &transfer);
+
+    assert_eq!(
+        client.try_mint_wrapped(&transfer, &sign(&env, &guardian_keys()[..2], &digest)),
+        Err(Ok(Error::WrappedAssetNotFound))
+    );
+}
+
+#[test]
+fn test_create_wrapped_asset_requires_token_admin() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let meta = near_asset_meta(&env);
+
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let digest = asset_meta_digest(&env, &client.address, &meta);
+
+    assert_eq!(
+        client.try_create_wrapped_asset(
+            &admin,
+            &meta,
+            &sign(&env, &guardian_keys()[..2], &digest),
+            &token
+        ),
+        Err(Ok(Error::NotTokenAdmin))
+    );
+}
+
+#[test]
+fn test_create_wrapped_asset_twice() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let meta = near_asset_meta(&env);
+    let token = create_wrapped(&env, &client, &meta);
+
+    let digest = asset_meta_digest(&env, &client.address, &meta);
+    assert_eq!(
+        client.try_create_wrapped_asset(
+            &admin,
+            &meta,
+            &sign(&env, &guardian_keys()[..2], &digest),
+            &token
+        ),
+        Err(Ok(Error::WrappedAssetExists))
+    );
+}
+
+#[test]
+fn test_burn_unwrapped_token() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+
+    let sender = Address::generate(&env);
+    let token = create_token(&env, &sender, 100);
+    let recipient = BytesN::from_array(&env, &[9u8; 32]);
+
+    assert_eq!(
+        client.try_burn_wrapped(&sender, &token, &100, &recipient),
+        Err(Ok(Error::WrappedAssetNotFound))
+    );
+}
+
+#[test]
+fn test_lock_deducts_fee() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+
+    let sender = Address::generate(&env);
+    let token = create_token(&env, &sender, 10_000);
+    set_fee_now(&env, &client, &admin, &token, &30, &5);
+
+    let recipient = BytesN::from_array(&env, &[1u8; 32]);
+    let nonce = client.lock_tokens(&sender, &token, &10_000, &2, &recipient);
+
+    // 0.3% of 10_000 is above the minimum fee
+    assert_eq!(client.view_lock_record(&nonce).unwrap().amount, 9_970);
+    assert_eq!(client.view_accrued_fees(&token), 30);
+    assert_eq!(
+        TokenClient::new(&env, &token).balance(&client.address),
+        10_000
+    );
+
+    let stats = client.view_token_stats(&token);
+    assert_eq!(stats.locked, 9_970);
+    assert_eq!(stats.outstanding, 9_970);
+    assert_eq!(stats.fees, 30);
+
+    // Per-token entries stay out of the size-limited instance entry
+    env.as_contract(&client.address, || {
+        let key = crate::stats::StatsKey::Token(token.clone());
+        assert!(env.storage().persistent().has(&key));
+        assert!(!env.storage().instance().has(&key));
+    });
+}
+
+#[test]
+fn test_lock_charges_min_fee() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+
+    let sender = Address::generate(&env);
+    let token = create_token(&env, &sender, 100);
+    set_fee_now(&env, &client, &admin, &token, &30, &5);
+
+    let recipient = BytesN::from_array(&env, &[1u8; 32]);
+    let nonce = client.lock_tokens(&sender, &token, &100, &2, &recipient);
+    assert_eq!(client.view_lock_record(&nonce).unwrap().amount, 95);
+
+    assert_eq!(
+        client.try_lock_tokens(&sender, &token, &5, &2, &recipient),
+        Err(Ok(Error::AmountBelowFee))
+    );
+}
+
+#[test]
+fn test_set_fee_rejects_invalid_bps() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let token = Address::generate(&env);
+
+    assert_eq!(
+        client.try_set_fee(&admin, &token, &10_001, &0),
+        Err(Ok(Error::InvalidFee))
+    );
+}
+
+#[test]
+fn test_withdraw_fees_to_treasury() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+
+    let sender = Address::generate(&env);
+    let token = create_token(&env, &sender, 1_000);
+    set_fee_now(&env, &client, &admin, &token, &100, &0);
+
+    let recipient = BytesN::from_array(&env, &[1u8; 32]);
+    client.lock_tokens(&sender, &token, &1_000, &2, &recipient);
+
+    assert_eq!(
+        client.try_withdraw_fees(&admin, &token),
+        Err(Ok(Error::NoTreasury))
+    );
+
+    let treasury = Address::generate(&env);
+    run_admin_action(&client, &admin, AdminAction::SetTreasury(treasury.clone()));
+    assert_eq!(client.withdraw_fees(&admin, &token), 10);
+    assert_eq!(client.view_accrued_fees(&token), 0);
+    assert_eq!(TokenClient::new(&env, &token).balance(&treasury), 10);
+    assert_eq!(client.withdraw_fees(&admin, &token), 0);
+}
+
+#[test]
+fn test_list_locks_paginates() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+
+    let sender = Address::generate(&env);
+    let token = create_token(&env, &sender, 500);
+    let recipient = BytesN::from_array(&env, &[1u8; 32]);
+    for _ in 0..5 {
+        client.lock_tokens(&sender, &token, &100, &2, &recipient);
+    }
+
+    let page = client.list_locks(&1, &2);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(1).unwrap().nonce, 2);
+
+    let page = client.list_locks(&4, &10);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(0).unwrap().nonce, 4);
+    assert_eq!(page.get(1).unwrap().nonce, 5);
+
+    assert_eq!(client.list_locks(&6, &10).len(), 0);
+}
+
+#[test]
+fn test_locks_by_sender() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let token = create_token(&env, &alice, 300);
+    StellarAssetClient::new(&env, &token).mint(&bob, &100);
+    let recipient = BytesN::from_array(&env, &[1u8; 32]);
+
+    client.lock_tokens(&alice, &token, &100, &2, &recipient);
+    client.lock_tokens(&bob, &token, &100, &2, &recipient);
+    client.lock_tokens(&alice, &token, &100, &2, &recipient);
+    client.lock_tokens(&alice, &token, &100, &2, &recipient);
+
+    assert_eq!(client.view_sender_lock_count(&alice), 3);
+
+    let page = client.locks_by_sender(&alice, &0, &2);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(0).unwrap().nonce, 1);
+    assert_eq!(page.get(1).unwrap().nonce, 3);
+
+    let page = client.locks_by_sender(&alice, &2, &2);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().nonce, 4);
+
+    let page = client.locks_by_sender(&bob, &0, &10);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().nonce, 2);
+}
+
+#[test]
+fn test_token_stats_per_direction() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+
+    let (token, released) = lock_new_token(&env, &client, 100);
+    let sender = Address::generate(&env);
+    StellarAssetClient::new(&env, &token).mint(&sender, &50);
+    let recipient = BytesN::from_array(&env, &[1u8; 32]);
+    let refunded = client.lock_tokens(&sender, &token, &50, &2, &recipient);
+    let (other_token, _) = lock_new_token(&env, &client, 7);
+
+    deliver(&env, &client, released);
+    client.release_tokens(&released, &released, &Address::generate(&env));
+
+    env.ledger()
+        .with_mut(|l| l.timestamp += REFUND_TIMEOUT_SECS + 1);
+    let signatures = attest(&env, &client, refunded, TransferStatus::Cancelled);
+    client.cancel_lock(&refunded, &signatures);
+    client.refund(&refunded);
+
+    let stats = client.view_token_stats(&token);
+    assert_eq!(stats.locked, 150);
+    assert_eq!(stats.released, 100);
+    assert_eq!(stats.refunded, 50);
+    assert_eq!(stats.outstanding, 0);
+    assert_eq!(stats.lock_count, 2);
+    assert_eq!(stats.release_count, 1);
+
+    assert_eq!(client.view_token_stats(&other_token).outstanding, 7);
+    assert_eq!(
+        TokenClient::new(&env, &other_token).balance(&client.address),
+        7
+    );
+}
+
+#[test]
+fn test_multisig_proposal_flow() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+
+    let owner2 = Address::generate(&env);
+    let owner3 = Address::generate(&env);
+    run_admin_action(
+        &client,
+        &admin,
+        AdminAction::GrantRole(Role::Owner, owner2.clone()),
+    );
+    run_admin_action(
+        &client,
+        &admin,
+        AdminAction::GrantRole(Role::Owner, owner3.clone()),
+    );
+    run_admin_action(&client, &admin, AdminAction::SetThreshold(2));
+    assert_eq!(client.view_role_members(&Role::Owner).len(), 3);
+    assert_eq!(client.view_threshold(), 2);
+
+    let treasury = Address::generate(&env);
+    let id = client.propose(&owner2, &AdminAction::SetTreasury(treasury.clone()));
+    assert_eq!(
+        client.try_execute_proposal(&id),
+        Err(Ok(Error::ThresholdNotReached))
+    );
+    assert_eq!(
+        client.try_approve(&owner2, &id),
+        Err(Ok(Error::AlreadyApproved))
+    );
+
+    client.approve(&owner3, &id);
+    client.execute_proposal(&id);
+    assert_eq!(client.view_treasury(), Some(treasury));
+    assert_eq!(client.view_proposal(&id), None);
+
+    assert_eq!(
+        client.try_execute_proposal(&id),
+        Err(Ok(Error::ProposalAlreadyExecuted))
+    );
+    assert_eq!(
+        client.try_approve(&admin, &id),
+        Err(Ok(Error::ProposalAlreadyExecuted))
+    );
+    assert_eq!(
+        client.try_execute_proposal(&(id + 1)),
+        Err(Ok(Error::ProposalNotFound))
+    );
+}
+
+#[test]
+fn test_revoked_owner_approval_does_not_count() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+
+    let owner2 = Address::generate(&env);
+    let owner3 = Address::generate(&env);
+    run_admin_action(
+        &client,
+        &admin,
+        AdminAction::GrantRole(Role::Owner, owner2.clone()),
+    );
+    run_admin_action(
+        &client,
+        &admin,
+        AdminAction::GrantRole(Role::Owner, owner3.clone()),
+    );
+    run_admin_action(&client, &admin, AdminAction::SetThreshold(2));
+
+    let id = client.propose(&owner2, &AdminAction::Unpause(true, true));
+
+    let revoke = client.propose(
+        &admin,
+        &AdminAction::RevokeRole(Role::Owner, owner2.clone()),
+    );
+    client.approve(&owner3, &revoke);
+    client.execute_proposal(&revoke);
+
+    assert_eq!(
+        client.try_execute_proposal(&id),
+        Err(Ok(Error::ThresholdNotReached))
+    );
+}
+
+#[test]
+fn test_owner_set_cannot_drop_below_threshold() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+
+    let id = client.propose(&admin, &AdminAction::RevokeRole(Role::Owner, admin.clone()));
+    assert_eq!(
+        client.try_execute_proposal(&id),
+        Err(Ok(Error::InvalidThreshold))
+    );
+
+    let id = client.propose(&admin, &AdminAction::SetThreshold(2));
+    assert_eq!(
+        client.try_execute_proposal(&id),
+        Err(Ok(Error::InvalidThreshold))
+    );
+}
+
+#[test]
+fn test_propose_requires_owner() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+
+    assert_eq!(
+        client.try_propose(&Address::generate(&env), &AdminAction::Unpause(true, true)),
+        Err(Ok(Error::NotAuthorized))
+    );
+}
+
+#[test]
+fn test_fee_manager_role() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let token = Address::generate(&env);
+
+    let fee_manager = Address::generate(&env);
+    assert_eq!(
+        client.try_set_fee(&fee_manager, &token, &30, &0),
+        Err(Ok(Error::NotAuthorized))
+    );
+
+    run_admin_action(
+        &client,
+        &admin,
+        AdminAction::GrantRole(Role::FeeManager, fee_manager.clone()),
+    );
+    set_fee_now(&env, &client, &fee_manager, &token, &30, &0);
+    assert_eq!(client.view_fee_config(&token).unwrap().bps, 30);
+
+    // Fee managers cannot register tokens
+    assert_eq!(
+        client.try_set_token_limit(&fee_manager, &token, &100, &10),
+        Err(Ok(Error::NotAuthorized))
+    );
+}
+
+#[test]
+fn test_role_change_emits_event() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+
+    let pauser = Address::generate(&env);
+    run_admin_action(
+        &client,
+        &admin,
+        AdminAction::GrantRole(Role::Pauser, pauser.clone()),
+    );
+
+    let granted = env.events().all().iter().any(|(contract, topics, data)| {
+        contract == client.address
+            && topics
+                == vec![
+                    &env,
+                    symbol_short!("role").into_val(&env),
+                    symbol_short!("granted").into_val(&env),
+                ]
+            && <(Role, Address)>::try_from_val(&env, &data) == Ok((Role::Pauser, pauser.clone()))
+    });
+    assert!(granted);
+}
+
+#[test]
+fn test_timelocked_fee_change() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let token = Address::generate(&env);
+
+    let id = client.set_fee(&admin, &token, &30, &0);
+    let entry = client.view_timelocked(&id).unwrap();
+    assert_eq!(entry.action, TimelockAction::SetFee(token.clone(), 30, 0));
+    assert_eq!(entry.eta, env.ledger().timestamp() + TIMELOCK_DELAY_SECS);
+    assert_eq!(client.view_fee_config(&token), None);
+    env.as_contract(&client.address, || {
+        let key = timelock::TimelockKey::Entry(id);
+        assert!(env.storage().persistent().has(&key));
+        assert!(!env.storage().instance().has(&key));
+    });
+
+    env.ledger()
+        .with_mut(|l| l.timestamp += TIMELOCK_DELAY_SECS - 1);
+    assert_eq!(
+        client.try_execute_timelocked(&id),
+        Err(Ok(Error::TimelockNotReady))
+    );
+
+    env.ledger().with_mut(|l| l.timestamp += 1);
+    client.execute_timelocked(&id);
+    assert_eq!(client.view_fee_config(&token).unwrap().bps, 30);
+    assert_eq!(
+        client.try_execute_timelocked(&id),
+        Err(Ok(Error::TimelockNotFound))
+    );
+    env.as_contract(&client.address, || {
+        assert!(!env
+            .storage()
+            .persistent()
+            .has(&timelock::TimelockKey::Entry(id)));
+    });
+}
+
+#[test]
+fn test_guardian_update_is_timelocked() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+
+    let new_guardians = guardian_pubkeys(&env, &guardian_keys()[..1]);
+    let proposal = client.propose(
+        &admin,
+        &AdminAction::UpdateGuardians(new_guardians.clone(), 1),
+    );
+    client.execute_proposal(&proposal);
+    assert_eq!(client.view_guardians().1, 2);
+
+    apply_timelocked(&env, &client, 1);
+    assert_eq!(client.view_guardians(), (new_guardians, 1));
+}
+
+#[test]
+fn test_guardians_cancel_timelocked_action() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let token = Address::generate(&env);
+
+    let id = client.set_token_limit(&admin, &token, &100, &10);
+    let vaa = governance_vaa(
+        &env,
+        &client.address,
+        &guardian_keys()[..2],
+        1,
+        GovernanceAction::CancelTimelock(id),
+    );
+    client.submit_governance(&vaa);
+    assert_eq!(client.view_timelocked(&id), None);
+
+    env.ledger()
+        .with_mut(|l| l.timestamp += TIMELOCK_DELAY_SECS);
+    assert_eq!(
+        client.try_execute_timelocked(&id),
+        Err(Ok(Error::TimelockNotFound))
+    );
+    assert_eq!(client.view_token_limit(&token), None);
+}
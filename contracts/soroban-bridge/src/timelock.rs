@@ -0,0 +1,64 @@
+use soroban_sdk::{contracttype, Address, BytesN, Env, Vec};
+
+use crate::set_persistent;
+
+/// Delay between scheduling a parameter change and executing it (48 hours)
+pub const TIMELOCK_DELAY_SECS: u64 = 172_800;
+
+/// Parameter changes that only take effect after the timelock delay
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum TimelockAction {
+    /// Replace the guardian set (guardians, quorum)
+    UpdateGuardians(Vec<BytesN<32>>, u32),
+    /// Set a token's fee (token, bps, min_fee)
+    SetFee(Address, u32, i128),
+    /// Set a token's governor limits (token, daily_limit, big_tx_threshold)
+    SetTokenLimit(Address, i128, i128),
+}
+
+/// A scheduled `TimelockAction`, removed once executed or cancelled
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimelockEntry {
+    pub id: u64,
+    pub action: TimelockAction,
+    /// Earliest ledger timestamp at which the action can be executed
+    pub eta: u64,
+}
+
+/// Storage keys for scheduled actions
+#[contracttype]
+pub enum TimelockKey {
+    /// Latest timelock id
+    Nonce,
+    /// Timelock id -> TimelockEntry (persistent)
+    Entry(u64),
+}
+
+/// Schedule `action` to be executable after the timelock delay
+pub fn schedule(env: &Env, action: TimelockAction) -> TimelockEntry {
+    let id: u64 = env
+        .storage()
+        .instance()
+        .get(&TimelockKey::Nonce)
+        .unwrap_or(0)
+        + 1;
+
+    let entry = TimelockEntry {
+        id,
+        action,
+        eta: env.ledger().timestamp() + TIMELOCK_DELAY_SECS,
+    };
+    set_persistent(env, &TimelockKey::Entry(id), &entry);
+    env.storage().instance().set(&TimelockKey::Nonce, &id);
+    entry
+}
+
+pub fn entry(env: &Env, id: u64) -> Option<TimelockEntry> {
+    env.storage().persistent().get(&TimelockKey::Entry(id))
+}
+
+pub fn remove(env: &Env, id: u64) {
+    env.storage().persistent().remove(&TimelockKey::Entry(id));
+}
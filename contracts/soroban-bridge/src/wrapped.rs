@@ -0,0 +1,165 @@
+use soroban_sdk::{contracttype, xdr::ToXdr, Address, BytesN, Env, String};
+
+use crate::set_persistent;
+
+/// Metadata of a foreign (NEAR-native) asset, attested by the guardians
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct AssetMeta {
+    /// Chain the asset is native to
+    pub token_chain: u32,
+    /// Asset identifier on its native chain (e.g. sha256 of the NEP-141 account)
+    pub token_address: BytesN<32>,
+    pub decimals: u32,
+    pub symbol: String,
+    pub name: String,
+}
+
+/// An inbound transfer of a foreign asset, to be minted as its wrapped token
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct WrappedTransfer {
+    pub token_chain: u32,
+    pub token_address: BytesN<32>,
+    /// Lock nonce on the source chain (replay protection)
+    pub nonce: u64,
+    pub recipient: Address,
+    pub amount: i128,
+}
+
+/// A burn of wrapped tokens, to be unlocked on the asset's native chain
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct BurnRecord {
+    pub nonce: u64,
+    pub token: Address,
+    pub amount: i128,
+    pub sender: Address,
+    pub recipient_chain: u32,
+    pub recipient: BytesN<32>,
+    pub timestamp: u64,
+}
+
+/// Storage keys for wrapped-asset data
+#[contracttype]
+pub enum WrappedKey {
+    /// (token_chain, token_address) -> wrapped token Address
+    Asset(u32, BytesN<32>),
+    /// Wrapped token Address -> AssetMeta
+    Meta(Address),
+    /// (source chain, source nonce) of inbound transfers already minted (persistent)
+    Minted(u32, u64),
+    /// Latest burn nonce
+    BurnNonce,
+    /// Burn nonce -> BurnRecord (persistent)
+    Burn(u64),
+}
+
+/// Message body guardians sign to attest an asset's metadata
+#[contracttype]
+#[derive(Clone)]
+struct AssetMetaBody {
+    contract: Address,
+    meta: AssetMeta,
+}
+
+/// Message body guardians sign to attest an inbound wrapped transfer
+#[contracttype]
+#[derive(Clone)]
+struct WrappedTransferBody {
+    contract: Address,
+    transfer: WrappedTransfer,
+}
+
+/// Compute the digest guardians sign to attest `meta`
+pub fn asset_meta_digest(env: &Env, contract: &Address, meta: &AssetMeta) -> BytesN<32> {
+    let body = AssetMetaBody {
+        contract: contract.clone(),
+        meta: meta.clone(),
+    };
+    env.crypto().sha256(&body.to_xdr(env)).into()
+}
+
+/// Compute the digest guardians sign to attest an inbound `transfer`
+pub fn wrapped_transfer_digest(
+    env: &Env,
+    contract: &Address,
+    transfer: &WrappedTransfer,
+) -> BytesN<32> {
+    let body = WrappedTransferBody {
+        contract: contract.clone(),
+        transfer: transfer.clone(),
+    };
+    env.crypto().sha256(&body.to_xdr(env)).into()
+}
+
+/// Wrapped token for a foreign asset, if one was created
+pub fn wrapped_asset(env: &Env, token_chain: u32, token_address: &BytesN<32>) -> Option<Address> {
+    env.storage()
+        .instance()
+        .get(&WrappedKey::Asset(token_chain, token_address.clone()))
+}
+
+/// Metadata of a wrapped token, if `token` is one
+pub fn asset_meta(env: &Env, token: &Address) -> Option<AssetMeta> {
+    env.storage()
+        .instance()
+        .get(&WrappedKey::Meta(token.clone()))
+}
+
+pub fn register_wrapped_asset(env: &Env, meta: &AssetMeta, token: &Address) {
+    env.storage().instance().set(
+        &WrappedKey::Asset(meta.token_chain, meta.token_address.clone()),
+        token,
+    );
+    env.storage()
+        .instance()
+        .set(&WrappedKey::Meta(token.clone()), meta);
+}
+
+/// Whether the transfer with `nonce` from `token_chain` was already minted
+///
+/// Nonces are only unique per source chain, so both make up the key.
+pub fn is_minted(env: &Env, token_chain: u32, nonce: u64) -> bool {
+    env.storage()
+        .persistent()
+        .has(&WrappedKey::Minted(token_chain, nonce))
+}
+
+pub fn mark_minted(env: &Env, token_chain: u32, nonce: u64) {
+    set_persistent(env, &WrappedKey::Minted(token_chain, nonce), &true);
+}
+
+/// Store a new burn record under the next burn nonce
+pub fn record_burn(
+    env: &Env,
+    token: &Address,
+    amount: i128,
+    sender: &Address,
+    recipient_chain: u32,
+    recipient: &BytesN<32>,
+) -> BurnRecord {
+    let nonce: u64 = env
+        .storage()
+        .instance()
+        .get(&WrappedKey::BurnNonce)
+        .unwrap_or(0)
+        + 1;
+
+    let record = BurnRecord {
+        nonce,
+        token: token.clone(),
+        amount,
+        sender: sender.clone(),
+        recipient_chain,
+        recipient: recipient.clone(),
+        timestamp: env.ledger().timestamp(),
+    };
+    set_persistent(env, &WrappedKey::Burn(nonce), &record);
+    env.storage().instance().set(&WrappedKey::BurnNonce, &nonce);
+    record
+}
+
+pub fn burn_record(env: &Env, nonce: u64) -> Option<BurnRecord> {
+    env.storage().persistent().get(&WrappedKey::Burn(nonce))
+}
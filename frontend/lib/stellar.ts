@@ -13,6 +13,56 @@ export interface BridgeContractClient {
   isVaaProcessed: (nonce: number) => Promise<boolean>;
 }
 
+/**
+ * Error codes returned by the Soroban bridge contract (`Error` in
+ * contracts/soroban-bridge/src/error.rs). Codes are stable.
+ */
+export enum BridgeContractError {
+  AlreadyInitialized = 1,
+  NotInitialized = 2,
+  InvalidQuorum = 3,
+  InvalidAmount = 4,
+  LockAlreadyExists = 5,
+  LockNotFound = 6,
+  InvalidStatusTransition = 7,
+  InboundPaused = 8,
+  OutboundPaused = 9,
+  NotAuthorized = 10,
+  InsufficientSignatures = 11,
+  GovernanceReplay = 12,
+  ReleaseAlreadyQueued = 13,
+  ReleaseNotQueued = 14,
+  ReleaseDelayed = 15,
+  InvalidLimit = 16,
+  RefundTimeoutNotReached = 17,
+  NoPendingAdmin = 18,
+  UnsupportedStorageVersion = 19,
+  WrappedAssetExists = 20,
+  WrappedAssetNotFound = 21,
+  NotTokenAdmin = 22,
+  TransferAlreadyProcessed = 23,
+  InvalidFee = 24,
+  AmountBelowFee = 25,
+  NoTreasury = 26,
+  ArithmeticOverflow = 27,
+  ProposalNotFound = 28,
+  ProposalAlreadyExecuted = 29,
+  AlreadyApproved = 30,
+  ThresholdNotReached = 31,
+  InvalidThreshold = 32,
+  TimelockNotFound = 33,
+  TimelockNotReady = 34,
+}
+
+/**
+ * Extract the bridge contract error code from a failed invocation, if any
+ * (host errors are reported as `Error(Contract, #<code>)`).
+ */
+export const parseBridgeContractError = (error: unknown): BridgeContractError | undefined => {
+  const match = String(error instanceof Error ? error.message : error).match(/Error\(Contract, #(\d+)\)/);
+  return match ? (Number(match[1]) as BridgeContractError) : undefined;
+};
+
 export class StellarBridgeClient {
   private server: StellarSDK.Horizon.Server;
   private contractId: string;
@@ -56,8 +106,9 @@ export class StellarBridgeClient {
         .addOperation(
           StellarSDK.Operation.invokeContractFunction({
             contract: this.contractId,
-            function: 'lock',
+            function: 'lock_tokens',
             args: [
+              StellarSDK.nativeToScVal(sourceKeys.publicKey(), { type: 'address' }),
               StellarSDK.nativeToScVal(token, { type: 'address' }),
               StellarSDK.nativeToScVal(amountInStroops, { type: 'i128' }),
               StellarSDK.nativeToScVal(recipientChain, { type: 'u32' }),
@@ -116,8 +167,9 @@ export class StellarBridgeClient {
         .addOperation(
           StellarSDK.Operation.invokeContractFunction({
             contract: this.contractId,
-            function: 'lock',
+            function: 'lock_tokens',
             args: [
+              StellarSDK.nativeToScVal(publicKey, { type: 'address' }),
               StellarSDK.nativeToScVal(token, { type: 'address' }),
               StellarSDK.nativeToScVal(amountInStroops, { type: 'i128' }),
               StellarSDK.nativeToScVal(recipientChain, { type: 'u32' }),
@@ -0,0 +1,205 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use anyhow::{bail, Context, Result};
+use bridge_shared::ChainId;
+use serde::{Deserialize, Serialize};
+
+/// Where the relayer keeps its per-chain cursors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckpointBackend {
+    /// JSON file replaced atomically on every save
+    File,
+    /// Tree in the relayer's sled database
+    Sled,
+}
+
+impl FromStr for CheckpointBackend {
+    type Err = anyhow::Error;
+
+    fn from_str(backend: &str) -> Result<Self> {
+        match backend {
+            "file" => Ok(CheckpointBackend::File),
+            "sled" => Ok(CheckpointBackend::Sled),
+            _ => bail!("Unknown checkpoint backend {:?}", backend),
+        }
+    }
+}
+
+/// Persistent position of the relayer on each chain
+///
+/// A cursor is saved only after the batch read up to it has been handled,
+/// so a restart resumes from the last completed batch. Cursors are opaque
+/// strings: a Soroban RPC event cursor for Stellar, the last burn sequence for
+/// NEAR.
+pub trait CheckpointStore: Send + Sync {
+    fn load(&self, chain: ChainId) -> Result<Option<String>>;
+
+    /// Replace the cursor of `chain`; durable once this returns
+    fn save(&self, chain: ChainId, cursor: &str) -> Result<()>;
+
+    /// Forget the cursor of `chain` so polling restarts from the default
+    fn reset(&self, chain: ChainId) -> Result<()>;
+}
+
+fn chain_key(chain: ChainId) -> &'static str {
+    match chain {
+        ChainId::Stellar => "stellar",
+        ChainId::Near => "near",
+    }
+}
+
+/// Cursors in a JSON file, written to a temporary file and renamed over it
+pub struct FileCheckpointStore {
+    path: PathBuf,
+    cursors: Mutex<BTreeMap<String, String>>,
+}
+
+impl FileCheckpointStore {
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let cursors = match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .with_context(|| format!("Corrupt checkpoint file {}", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e).context("Failed to read checkpoint file"),
+        };
+
+        Ok(Self {
+            path,
+            cursors: Mutex::new(cursors),
+        })
+    }
+
+    fn write(&self, cursors: &BTreeMap<String, String>) -> Result<()> {
+        let tmp = self.path.with_extension("tmp");
+        let mut file = fs::File::create(&tmp)
+            .with_context(|| format!("Failed to create {}", tmp.display()))?;
+        file.write_all(&serde_json::to_vec_pretty(cursors)?)?;
+        file.sync_all()?;
+        fs::rename(&tmp, &self.path)
+            .with_context(|| format!("Failed to replace {}", self.path.display()))?;
+        sync_parent(&self.path)
+    }
+
+    fn update(&self, f: impl FnOnce(&mut BTreeMap<String, String>)) -> Result<()> {
+        let mut cursors = self.cursors.lock().expect("checkpoint lock poisoned");
+        let mut updated = cursors.clone();
+        f(&mut updated);
+        self.write(&updated)?;
+        *cursors = updated;
+        Ok(())
+    }
+}
+
+/// Make a rename durable by syncing the directory holding it
+fn sync_parent(path: &Path) -> Result<()> {
+    match path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        Some(dir) => fs::File::open(dir)?.sync_all()?,
+        None => fs::File::open(".")?.sync_all()?,
+    }
+    Ok(())
+}
+
+impl CheckpointStore for FileCheckpointStore {
+    fn load(&self, chain: ChainId) -> Result<Option<String>> {
+        let cursors = self.cursors.lock().expect("checkpoint lock poisoned");
+        Ok(cursors.get(chain_key(chain)).cloned())
+    }
+
+    fn save(&self, chain: ChainId, cursor: &str) -> Result<()> {
+        self.update(|cursors| {
+            cursors.insert(chain_key(chain).to_string(), cursor.to_string());
+        })
+    }
+
+    fn reset(&self, chain: ChainId) -> Result<()> {
+        self.update(|cursors| {
+            cursors.remove(chain_key(chain));
+        })
+    }
+}
+
+/// Cursors in the `checkpoints` tree of a sled database
+pub struct SledCheckpointStore {
+    tree: sled::Tree,
+}
+
+impl SledCheckpointStore {
+    pub fn open(db: &sled::Db) -> Result<Self> {
+        Ok(Self {
+            tree: db.open_tree("checkpoints")?,
+        })
+    }
+}
+
+impl CheckpointStore for SledCheckpointStore {
+    fn load(&self, chain: ChainId) -> Result<Option<String>> {
+        let Some(cursor) = self.tree.get(chain_key(chain))? else {
+            return Ok(None);
+        };
+        Ok(Some(
+            String::from_utf8(cursor.to_vec()).context("Corrupt checkpoint")?,
+        ))
+    }
+
+    fn save(&self, chain: ChainId, cursor: &str) -> Result<()> {
+        self.tree.insert(chain_key(chain), cursor.as_bytes())?;
+        self.tree.flush()?;
+        Ok(())
+    }
+
+    fn reset(&self, chain: ChainId) -> Result<()> {
+        self.tree.remove(chain_key(chain))?;
+        self.tree.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exercise(store: &dyn CheckpointStore) {
+        assert_eq!(store.load(ChainId::Stellar).unwrap(), None);
+
+        store.save(ChainId::Stellar, "12345-1").unwrap();
+        store.save(ChainId::Near, "900").unwrap();
+        store.save(ChainId::Near, "901").unwrap();
+        assert_eq!(
+            store.load(ChainId::Stellar).unwrap().as_deref(),
+            Some("12345-1")
+        );
+        assert_eq!(store.load(ChainId::Near).unwrap().as_deref(), Some("901"));
+
+        store.reset(ChainId::Stellar).unwrap();
+        assert_eq!(store.load(ChainId::Stellar).unwrap(), None);
+        assert_eq!(store.load(ChainId::Near).unwrap().as_deref(), Some("901"));
+    }
+
+    #[test]
+    fn test_file_store_survives_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("checkpoints.json");
+
+        exercise(&FileCheckpointStore::open(&path).unwrap());
+
+        let reopened = FileCheckpointStore::open(&path).unwrap();
+        assert_eq!(
+            reopened.load(ChainId::Near).unwrap().as_deref(),
+            Some("901")
+        );
+        assert!(!path.with_extension("tmp").exists());
+    }
+
+    #[test]
+    fn test_sled_store() {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        exercise(&SledCheckpointStore::open(&db).unwrap());
+    }
+}
@@ -1,11 +1,18 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 
+use crate::checkpoint::CheckpointBackend;
+use crate::finality::NearFinality;
+use crate::supply::SupplyCheckMode;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     /// Stellar Horizon API URL
     pub stellar_horizon_url: String,
 
+    /// Soroban RPC URL, used to simulate contract view calls
+    pub soroban_rpc_url: String,
+
     /// Stellar bridge contract address
     pub stellar_bridge_contract: String,
 
@@ -20,6 +27,36 @@ pub struct Config {
 
     /// Poll interval in seconds
     pub poll_interval_secs: u64,
+
+    /// Seconds between supply invariant checks while running
+    pub supply_check_interval_secs: u64,
+
+    /// Response to NEAR supply exceeding the Stellar locks
+    pub supply_check_mode: SupplyCheckMode,
+
+    /// Webhook receiving supply alerts (JSON POST)
+    pub alert_webhook_url: Option<String>,
+
+    /// Directory holding the relayer's persistent state
+    pub data_dir: String,
+
+    /// Store for the per-chain polling cursors
+    pub checkpoint_backend: CheckpointBackend,
+
+    /// Delay before the first redelivery; doubles on each further attempt
+    pub retry_base_delay_secs: u64,
+
+    /// Longest delay between deliveries of one VAA
+    pub retry_max_delay_secs: u64,
+
+    /// Deliveries of one VAA before it is marked failed
+    pub max_delivery_attempts: u32,
+
+    /// Finality NEAR burns must reach before they are signed
+    pub near_finality: NearFinality,
+
+    /// Closed Stellar ledgers required after a lock before it is signed
+    pub stellar_confirmation_depth: u64,
 }
 
 impl Config {
@@ -28,6 +65,9 @@ impl Config {
             stellar_horizon_url: std::env::var("STELLAR_HORIZON_URL")
                 .unwrap_or_else(|_| "https://horizon-testnet.stellar.org".to_string()),
             
+            soroban_rpc_url: std::env::var("SOROBAN_RPC_URL")
+                .unwrap_or_else(|_| "https://soroban-testnet.stellar.org".to_string()),
+
             stellar_bridge_contract: std::env::var("STELLAR_BRIDGE_CONTRACT")
                 .context("STELLAR_BRIDGE_CONTRACT not set")?,
             
@@ -44,6 +84,50 @@ impl Config {
                 .unwrap_or_else(|_| "10".to_string())
                 .parse()
                 .context("Invalid POLL_INTERVAL_SECS")?,
+
+            supply_check_interval_secs: std::env::var("SUPPLY_CHECK_INTERVAL_SECS")
+                .unwrap_or_else(|_| "300".to_string())
+                .parse()
+                .context("Invalid SUPPLY_CHECK_INTERVAL_SECS")?,
+
+            supply_check_mode: std::env::var("SUPPLY_CHECK_MODE")
+                .unwrap_or_else(|_| "alert".to_string())
+                .parse()
+                .context("Invalid SUPPLY_CHECK_MODE")?,
+
+            alert_webhook_url: std::env::var("ALERT_WEBHOOK_URL").ok(),
+
+            data_dir: std::env::var("DATA_DIR").unwrap_or_else(|_| "relayer-data".to_string()),
+
+            checkpoint_backend: std::env::var("CHECKPOINT_BACKEND")
+                .unwrap_or_else(|_| "file".to_string())
+                .parse()
+                .context("Invalid CHECKPOINT_BACKEND")?,
+
+            retry_base_delay_secs: std::env::var("RETRY_BASE_DELAY_SECS")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()
+                .context("Invalid RETRY_BASE_DELAY_SECS")?,
+
+            retry_max_delay_secs: std::env::var("RETRY_MAX_DELAY_SECS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()
+                .context("Invalid RETRY_MAX_DELAY_SECS")?,
+
+            max_delivery_attempts: std::env::var("MAX_DELIVERY_ATTEMPTS")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .context("Invalid MAX_DELIVERY_ATTEMPTS")?,
+
+            near_finality: std::env::var("NEAR_FINALITY")
+                .unwrap_or_else(|_| "final".to_string())
+                .parse()
+                .context("Invalid NEAR_FINALITY")?,
+
+            stellar_confirmation_depth: std::env::var("STELLAR_CONFIRMATION_DEPTH")
+                .unwrap_or_else(|_| "1".to_string())
+                .parse()
+                .context("Invalid STELLAR_CONFIRMATION_DEPTH")?,
         })
     }
 }
@@ -0,0 +1,97 @@
+use anyhow::{Context, Result};
+use bridge_shared::ChainId;
+use serde::{Deserialize, Serialize};
+
+use crate::outbox::entry_key;
+
+/// A transfer the relayer gave up on, with the reason
+///
+/// The transfer itself stays in the outbox in the `failed` state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub origin_chain: ChainId,
+    pub nonce: u64,
+    pub reason: String,
+    pub attempts: u32,
+    /// Unix time (seconds) the transfer was given up on
+    pub failed_at: u64,
+}
+
+/// Dead letters, kept in the `dead_letters` tree of the database
+///
+/// Letters are written together with their outbox entry (see
+/// `Outbox::update_with_letter`).
+pub struct DeadLetterQueue {
+    pub(crate) tree: sled::Tree,
+}
+
+impl DeadLetterQueue {
+    pub fn open(db: &sled::Db) -> Result<Self> {
+        Ok(Self {
+            tree: db.open_tree("dead_letters")?,
+        })
+    }
+
+    pub fn get(&self, origin_chain: ChainId, nonce: u64) -> Result<Option<DeadLetter>> {
+        self.tree
+            .get(entry_key(origin_chain, nonce))?
+            .map(|bytes| serde_json::from_slice(&bytes).context("Corrupt dead letter"))
+            .transpose()
+    }
+
+    /// Remove and return a dead letter
+    pub fn remove(&self, origin_chain: ChainId, nonce: u64) -> Result<Option<DeadLetter>> {
+        let removed = self
+            .tree
+            .remove(entry_key(origin_chain, nonce))?
+            .map(|bytes| serde_json::from_slice(&bytes).context("Corrupt dead letter"))
+            .transpose()?;
+        self.tree.flush()?;
+        Ok(removed)
+    }
+
+    /// Every dead letter, by origin chain then nonce
+    pub fn list(&self) -> Result<Vec<DeadLetter>> {
+        self.tree
+            .iter()
+            .values()
+            .map(|bytes| serde_json::from_slice(&bytes?).context("Corrupt dead letter"))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_list_remove() {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        let dlq = DeadLetterQueue::open(&db).unwrap();
+
+        for (origin_chain, nonce) in [(ChainId::Near, 1), (ChainId::Stellar, 7)] {
+            let letter = DeadLetter {
+                origin_chain,
+                nonce,
+                reason: "rejected".to_string(),
+                attempts: 10,
+                failed_at: 100,
+            };
+            dlq.tree
+                .insert(
+                    entry_key(origin_chain, nonce),
+                    serde_json::to_vec(&letter).unwrap(),
+                )
+                .unwrap();
+        }
+
+        let listed = dlq.list().unwrap();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].origin_chain, ChainId::Stellar);
+
+        assert!(dlq.get(ChainId::Near, 1).unwrap().is_some());
+        assert!(dlq.remove(ChainId::Near, 1).unwrap().is_some());
+        assert!(dlq.remove(ChainId::Near, 1).unwrap().is_none());
+        assert_eq!(dlq.list().unwrap().len(), 1);
+    }
+}
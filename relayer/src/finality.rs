@@ -0,0 +1,187 @@
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+use bridge_shared::ChainId;
+use serde::{Deserialize, Serialize};
+
+use crate::outbox::{entry_key, OutboxEntry};
+
+/// NEAR RPC finality level the relayer reads blocks at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NearFinality {
+    /// Latest block, may still be reorganised
+    Optimistic,
+    /// Doomslug-final: reverting needs a slashable double sign
+    NearFinal,
+    /// BFT-final
+    Final,
+}
+
+impl NearFinality {
+    /// Value of the RPC `finality` parameter
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NearFinality::Optimistic => "optimistic",
+            NearFinality::NearFinal => "near-final",
+            NearFinality::Final => "final",
+        }
+    }
+}
+
+impl FromStr for NearFinality {
+    type Err = anyhow::Error;
+
+    fn from_str(finality: &str) -> Result<Self> {
+        match finality {
+            "optimistic" => Ok(NearFinality::Optimistic),
+            "near-final" => Ok(NearFinality::NearFinal),
+            "final" => Ok(NearFinality::Final),
+            _ => bail!("Unknown NEAR finality {:?}", finality),
+        }
+    }
+}
+
+/// When an event on each chain is settled enough to sign
+#[derive(Debug, Clone, Copy)]
+pub struct FinalityPolicy {
+    /// NEAR events count once their block is reached at this finality
+    pub near_finality: NearFinality,
+    /// Closed Stellar ledgers required after the one holding the event
+    pub stellar_depth: u64,
+}
+
+impl FinalityPolicy {
+    /// Blocks or ledgers required on top of the event's own
+    pub fn depth(&self, chain: ChainId) -> u64 {
+        match chain {
+            ChainId::Stellar => self.stellar_depth,
+            // The head is already read at the configured finality
+            ChainId::Near => 0,
+        }
+    }
+
+    /// Whether an event at `height` is settled when the chain is at `head`
+    ///
+    /// Height 0 means the source did not say where the event is, so it never
+    /// counts as settled.
+    pub fn is_final(&self, chain: ChainId, height: u64, head: u64) -> bool {
+        height != 0 && height.saturating_add(self.depth(chain)) <= head
+    }
+}
+
+/// Observed events waiting for finality, kept in the `pending` tree
+///
+/// Events are moved into the outbox, and only then signed, once their chain
+/// has advanced past the required depth. Keeping them on disk lets the
+/// polling cursor move on without losing events that are still settling.
+pub struct PendingObservations {
+    tree: sled::Tree,
+}
+
+impl PendingObservations {
+    pub fn open(db: &sled::Db) -> Result<Self> {
+        Ok(Self {
+            tree: db.open_tree("pending")?,
+        })
+    }
+
+    /// Buffer an observed event; `false` if it is already buffered
+    pub fn hold(&self, entry: &OutboxEntry) -> Result<bool> {
+        let key = entry_key(entry.origin_chain, entry.nonce());
+        let inserted = self
+            .tree
+            .compare_and_swap(key, None as Option<&[u8]>, Some(serde_json::to_vec(entry)?))?
+            .is_ok();
+        self.tree.flush()?;
+        Ok(inserted)
+    }
+
+    /// Buffered events from `origin_chain`, by nonce
+    pub fn by_origin(&self, origin_chain: ChainId) -> Result<Vec<OutboxEntry>> {
+        self.tree
+            .scan_prefix([origin_chain as u8])
+            .values()
+            .map(|bytes| serde_json::from_slice(&bytes?).context("Corrupt pending observation"))
+            .collect()
+    }
+
+    pub fn remove(&self, origin_chain: ChainId, nonce: u64) -> Result<()> {
+        self.tree.remove(entry_key(origin_chain, nonce))?;
+        self.tree.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LockEvent;
+
+    #[test]
+    fn test_stellar_waits_for_depth_and_near_for_head() {
+        let policy = FinalityPolicy {
+            near_finality: NearFinality::Final,
+            stellar_depth: 3,
+        };
+        assert!(!policy.is_final(ChainId::Stellar, 100, 102));
+        assert!(policy.is_final(ChainId::Stellar, 100, 103));
+        assert!(!policy.is_final(ChainId::Near, 100, 99));
+        assert!(policy.is_final(ChainId::Near, 100, 100));
+
+        assert_eq!(
+            "near-final".parse::<NearFinality>().unwrap().as_str(),
+            "near-final"
+        );
+        assert!("finalized".parse::<NearFinality>().is_err());
+    }
+
+    #[test]
+    fn test_event_without_height_is_never_final() {
+        let policy = FinalityPolicy {
+            near_finality: NearFinality::Final,
+            stellar_depth: 0,
+        };
+        assert!(!policy.is_final(ChainId::Stellar, 0, 100));
+        assert!(!policy.is_final(ChainId::Near, 0, 100));
+
+        let event = serde_json::json!({
+            "event_type": "lock",
+            "asset": "aa",
+            "amount": 100,
+            "sender": "sender",
+            "recipient_chain": 2,
+            "recipient": vec![7u8; 32],
+            "nonce": 4,
+            "timestamp": 0,
+        });
+        assert!(serde_json::from_value::<LockEvent>(event).is_err());
+    }
+
+    #[test]
+    fn test_pending_is_grouped_by_origin() {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        let pending = PendingObservations::open(&db).unwrap();
+
+        let event = LockEvent {
+            event_type: "lock".to_string(),
+            asset: "aa".to_string(),
+            amount: 100,
+            sender: "sender".to_string(),
+            recipient_chain: ChainId::Near as u8,
+            recipient: [7u8; 32],
+            nonce: 4,
+            timestamp: 0,
+            height: 10,
+        };
+        let entry = OutboxEntry::observed(ChainId::Stellar, ChainId::Near, event);
+        assert!(pending.hold(&entry).unwrap());
+        assert!(!pending.hold(&entry).unwrap());
+
+        assert_eq!(pending.by_origin(ChainId::Stellar).unwrap().len(), 1);
+        assert!(pending.by_origin(ChainId::Near).unwrap().is_empty());
+
+        pending.remove(ChainId::Stellar, 4).unwrap();
+        assert!(pending.by_origin(ChainId::Stellar).unwrap().is_empty());
+    }
+}
@@ -1,58 +1,382 @@
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use reqwest::Client;
 use serde_json::Value;
+use stellar_xdr::curr::{
+    Hash, HostFunction, InvokeContractArgs, InvokeHostFunctionOp, Limits, Memo, MuxedAccount,
+    Operation, OperationBody, Preconditions, ReadXdr, ScAddress, ScVal, SequenceNumber,
+    Transaction, TransactionEnvelope, TransactionExt, TransactionV1Envelope, Uint256, VecM,
+    WriteXdr,
+};
 
 use crate::LockEvent;
 
+/// Events requested per `getEvents` call
+const EVENT_PAGE_SIZE: u32 = 100;
+
 pub struct HorizonClient {
     client: Client,
     base_url: String,
+    rpc_url: String,
+}
+
+/// Contract address from a strkey (`C...`) or 32 hex-encoded bytes
+pub fn contract_address(contract_id: &str) -> Result<ScAddress> {
+    if let Ok(address) = contract_id.parse::<ScAddress>() {
+        return Ok(address);
+    }
+
+    let bytes: [u8; 32] = hex::decode(contract_id)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .with_context(|| format!("Invalid Soroban contract id {:?}", contract_id))?;
+    Ok(ScAddress::Contract(Hash(bytes)))
+}
+
+/// 32-byte contract id from a strkey (`C...`) or hex
+pub fn contract_id(contract_id: &str) -> Result<[u8; 32]> {
+    match contract_address(contract_id)? {
+        ScAddress::Contract(Hash(id)) => Ok(id),
+        _ => bail!("{} is not a contract", contract_id),
+    }
+}
+
+/// Field `name` of a `#[contracttype]` struct value
+fn struct_field<'a>(value: &'a ScVal, name: &str) -> Option<&'a ScVal> {
+    let ScVal::Map(Some(map)) = value else {
+        return None;
+    };
+    map.iter()
+        .find(|entry| matches!(&entry.key, ScVal::Symbol(key) if key.as_slice() == name.as_bytes()))
+        .map(|entry| &entry.val)
+}
+
+/// Field `name` of a `#[contracttype]` struct value, converted to `T`
+fn field<T: TryFrom<ScVal>>(value: &ScVal, name: &str) -> Result<T> {
+    struct_field(value, name)
+        .and_then(|field| T::try_from(field.clone()).ok())
+        .with_context(|| format!("Missing or invalid field {}", name))
+}
+
+/// Lock event from the `LockRecord` published by `lock_tokens` in `ledger`
+fn lock_event(record: &ScVal, ledger: u64) -> Result<LockEvent> {
+    let Some(ScVal::Address(ScAddress::Contract(Hash(token)))) = struct_field(record, "token")
+    else {
+        bail!("Lock event without a token contract");
+    };
+    let Some(ScVal::Address(sender)) = struct_field(record, "sender") else {
+        bail!("Lock event without a sender");
+    };
+    let recipient: Vec<u8> = field(record, "recipient")?;
+
+    Ok(LockEvent {
+        event_type: "lock".to_string(),
+        asset: hex::encode(token),
+        amount: u128::try_from(field::<i128>(record, "amount")?)?,
+        sender: sender.to_string(),
+        recipient_chain: u8::try_from(field::<u32>(record, "recipient_chain")?)?,
+        recipient: recipient
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("Lock event recipient is not 32 bytes"))?,
+        nonce: field(record, "nonce")?,
+        timestamp: field(record, "timestamp")?,
+        height: ledger,
+    })
+}
+
+/// Base64 XDR of a `Symbol` topic
+fn symbol_topic(symbol: &str) -> Result<String> {
+    let symbol = symbol
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Invalid symbol {:?}", symbol))?;
+    Ok(ScVal::Symbol(symbol).to_xdr_base64(Limits::none())?)
+}
+
+/// Unsigned transaction envelope invoking `function` on `contract`
+///
+/// The source is the all-zero account: simulation of a read-only call
+/// neither loads nor charges it.
+fn invoke_envelope(contract: ScAddress, function: &str, args: Vec<ScVal>) -> Result<String> {
+    let invoke = InvokeHostFunctionOp {
+        host_function: HostFunction::InvokeContract(InvokeContractArgs {
+            contract_address: contract,
+            function_name: function
+                .try_into()
+                .map_err(|_| anyhow::anyhow!("Invalid function name {:?}", function))?,
+            args: args.try_into()?,
+        }),
+        auth: VecM::default(),
+    };
+    let tx = Transaction {
+        source_account: MuxedAccount::Ed25519(Uint256([0; 32])),
+        fee: 100,
+        seq_num: SequenceNumber(0),
+        cond: Preconditions::None,
+        memo: Memo::None,
+        operations: vec![Operation {
+            source_account: None,
+            body: OperationBody::InvokeHostFunction(invoke),
+        }]
+        .try_into()?,
+        ext: TransactionExt::V0,
+    };
+    let envelope = TransactionEnvelope::Tx(TransactionV1Envelope {
+        tx,
+        signatures: VecM::default(),
+    });
+    Ok(envelope.to_xdr_base64(Limits::none())?)
 }
 
 impl HorizonClient {
-    pub fn new(base_url: &str) -> Self {
+    pub fn new(base_url: &str, rpc_url: &str) -> Self {
         Self {
             client: Client::new(),
             base_url: base_url.to_string(),
+            rpc_url: rpc_url.to_string(),
+        }
+    }
+
+    /// Call a Soroban RPC method and return its result
+    async fn rpc(&self, method: &str, params: Value) -> Result<Value> {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+
+        let mut response: Value = self
+            .client
+            .post(&self.rpc_url)
+            .json(&request)
+            .send()
+            .await?
+            .json()
+            .await?;
+        if let Some(error) = response.get("error") {
+            bail!("Soroban RPC {} failed: {}", method, error);
+        }
+        Ok(response["result"].take())
+    }
+
+    /// Simulate a call of `function` on `contract_id` through Soroban RPC
+    /// and return its result
+    async fn simulate(&self, contract_id: &str, function: &str, args: Vec<ScVal>) -> Result<ScVal> {
+        let envelope = invoke_envelope(contract_address(contract_id)?, function, args)?;
+        let result = self
+            .rpc(
+                "simulateTransaction",
+                serde_json::json!({ "transaction": envelope }),
+            )
+            .await?;
+        if let Some(error) = result.get("error") {
+            bail!("Simulation of {} failed: {}", function, error);
         }
+
+        let xdr = result["results"][0]["xdr"]
+            .as_str()
+            .with_context(|| format!("Unexpected simulation response for {}", function))?;
+        Ok(ScVal::from_xdr_base64(xdr, Limits::none())?)
     }
 
+    /// Lock events of the bridge after `cursor`, and the cursor to continue
+    /// from
+    ///
+    /// Read with Soroban RPC `getEvents`; the cursor `now` starts at the
+    /// latest ledger. Each event carries the ledger that holds it.
     pub async fn get_contract_events(
         &self,
         contract_id: &str,
-        cursor: &mut String,
-    ) -> Result<Vec<LockEvent>> {
-        // Query Horizon for contract events
-        // In production: use proper Horizon SDK or API
-        let url = format!(
-            "{}/contracts/{}/events?cursor={}",
-            self.base_url, contract_id, cursor
-        );
+        cursor: &str,
+    ) -> Result<(Vec<LockEvent>, String)> {
+        let mut params = serde_json::json!({
+            "filters": [{
+                "type": "contract",
+                "contractIds": [contract_address(contract_id)?.to_string()],
+                "topics": [[symbol_topic("bridge")?, symbol_topic("lock")?]],
+            }],
+            "pagination": { "limit": EVENT_PAGE_SIZE },
+        });
+        if cursor == "now" {
+            params["startLedger"] = self.get_latest_ledger().await?.into();
+        } else {
+            params["pagination"]["cursor"] = cursor.into();
+        }
+        let result = self.rpc("getEvents", params).await?;
 
-        let response: Value = self.client.get(&url).send().await?.json().await?;
+        let mut events = Vec::new();
+        for event in result["events"]
+            .as_array()
+            .context("Unexpected getEvents response")?
+        {
+            if event.get("inSuccessfulContractCall").and_then(Value::as_bool) == Some(false) {
+                continue;
+            }
+            let ledger = event["ledger"]
+                .as_u64()
+                .context("Contract event without a ledger")?;
+            let value = event["value"]
+                .as_str()
+                .context("Contract event without a value")?;
+            let record = ScVal::from_xdr_base64(value, Limits::none())?;
+            events.push(lock_event(&record, ledger)?);
+        }
 
-        // Parse events (simplified - implement proper parsing)
-        let events = vec![];
+        let next_cursor = result["cursor"]
+            .as_str()
+            .context("getEvents response without a cursor")?
+            .to_string();
+        Ok((events, next_cursor))
+    }
 
-        // Update cursor for next poll
-        if let Some(new_cursor) = response.get("cursor").and_then(|c| c.as_str()) {
-            *cursor = new_cursor.to_string();
-        }
+    /// Sequence of the latest closed ledger Horizon has ingested
+    pub async fn get_latest_ledger(&self) -> Result<u64> {
+        let response: Value = self.client.get(&self.base_url).send().await?.json().await?;
+        response
+            .get("history_latest_ledger")
+            .and_then(Value::as_u64)
+            .context("Unexpected response from Horizon root")
+    }
 
-        Ok(events)
+    /// Locked and not yet released amount of `asset` in the bridge contract
+    ///
+    /// `asset` is the hex VAA asset id, which for Stellar assets is the id
+    /// of the Soroban token contract.
+    pub async fn get_outstanding_locks(&self, contract_id: &str, asset: &str) -> Result<u128> {
+        let token = ScVal::Address(contract_address(asset)?);
+        let stats = self
+            .simulate(contract_id, "view_token_stats", vec![token])
+            .await?;
+        struct_field(&stats, "outstanding")
+            .and_then(|outstanding| i128::try_from(outstanding.clone()).ok())
+            .and_then(|outstanding| u128::try_from(outstanding).ok())
+            .with_context(|| format!("Unexpected view_token_stats result for {}", asset))
     }
 
+    /// Whether the bridge released (or queued the release of) the NEAR burn
+    /// with outbound sequence `burn_sequence`
+    pub async fn is_burn_released(&self, contract_id: &str, burn_sequence: u64) -> Result<bool> {
+        let args = vec![ScVal::from(burn_sequence)];
+        let released = self.simulate(contract_id, "is_burn_released", args).await?;
+        bool::try_from(released)
+            .map_err(|_| anyhow::anyhow!("Unexpected result of is_burn_released"))
+    }
+
+    /// Submit a release of a NEAR burn to the bridge
+    ///
+    /// Not implemented: the relayer holds no Stellar signing key yet. Failing
+    /// keeps the outbox entry signed and retryable instead of marking a
+    /// release that was never sent as submitted.
     pub async fn call_release(&self, contract_id: &str, vaa_bytes: Vec<u8>) -> Result<()> {
-        // Build and submit Stellar transaction calling release()
-        // In production: use stellar-sdk to build TX, sign with relayer key, submit
-        
-        // Placeholder
-        tracing::info!(
-            "Would submit release TX to contract {} with VAA of {} bytes",
+        bail!(
+            "Release submission to {} is not implemented ({}-byte VAA not sent)",
             contract_id,
             vaa_bytes.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stellar_xdr::curr::{AccountId, PublicKey, ScMap, ScMapEntry};
+
+    #[test]
+    fn test_contract_address_accepts_strkey_and_hex() {
+        let id = [7u8; 32];
+        let strkey = ScAddress::Contract(Hash(id)).to_string();
+        assert!(strkey.starts_with('C'));
+
+        assert_eq!(
+            contract_address(&strkey).unwrap(),
+            ScAddress::Contract(Hash(id))
+        );
+        assert_eq!(
+            contract_address(&hex::encode(id)).unwrap(),
+            ScAddress::Contract(Hash(id))
         );
+        assert!(contract_address("abcd").is_err());
+    }
+
+    #[test]
+    fn test_struct_field() {
+        let entry = |key: &str, val: ScVal| ScMapEntry {
+            key: ScVal::Symbol(key.try_into().unwrap()),
+            val,
+        };
+        let stats = ScVal::Map(Some(ScMap(
+            vec![
+                entry("locked", ScVal::from(10i128)),
+                entry("outstanding", ScVal::from(7i128)),
+            ]
+            .try_into()
+            .unwrap(),
+        )));
+
+        assert_eq!(
+            struct_field(&stats, "outstanding"),
+            Some(&ScVal::from(7i128))
+        );
+        assert_eq!(struct_field(&stats, "fees"), None);
+        assert_eq!(struct_field(&ScVal::from(7i128), "outstanding"), None);
+    }
 
-        Ok(())
+    #[test]
+    fn test_lock_event_from_record() {
+        let entry = |key: &str, val: ScVal| ScMapEntry {
+            key: ScVal::Symbol(key.try_into().unwrap()),
+            val,
+        };
+        let sender = ScAddress::Account(AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(
+            [3u8; 32],
+        ))));
+        let record = ScVal::Map(Some(ScMap(
+            vec![
+                entry("amount", ScVal::from(500i128)),
+                entry("nonce", ScVal::from(4u64)),
+                entry("recipient", ScVal::try_from([9u8; 32]).unwrap()),
+                entry("recipient_chain", ScVal::from(2u32)),
+                entry("sender", ScVal::Address(sender.clone())),
+                entry("timestamp", ScVal::from(1_000u64)),
+                entry("token", ScVal::Address(ScAddress::Contract(Hash([7u8; 32])))),
+            ]
+            .try_into()
+            .unwrap(),
+        )));
+
+        let event = lock_event(&record, 77).unwrap();
+        assert_eq!(event.asset, hex::encode([7u8; 32]));
+        assert_eq!(event.amount, 500);
+        assert_eq!(event.sender, sender.to_string());
+        assert_eq!(event.recipient_chain, 2);
+        assert_eq!(event.recipient, [9u8; 32]);
+        assert_eq!(event.nonce, 4);
+        assert_eq!(event.height, 77);
+
+        assert!(lock_event(&ScVal::from(4u64), 77).is_err());
+    }
+
+    #[test]
+    fn test_invoke_envelope_round_trips() {
+        let contract = ScAddress::Contract(Hash([7u8; 32]));
+        let args = vec![ScVal::from(2u32), ScVal::from(9u64)];
+        let xdr = invoke_envelope(contract.clone(), "is_vaa_processed", args.clone()).unwrap();
+
+        let TransactionEnvelope::Tx(envelope) =
+            TransactionEnvelope::from_xdr_base64(xdr, Limits::none()).unwrap()
+        else {
+            panic!("expected a v1 envelope");
+        };
+        let OperationBody::InvokeHostFunction(op) = &envelope.tx.operations[0].body else {
+            panic!("expected an invocation");
+        };
+        let HostFunction::InvokeContract(invoke) = &op.host_function else {
+            panic!("expected a contract call");
+        };
+        assert_eq!(invoke.contract_address, contract);
+        assert_eq!(
+            invoke.function_name.to_utf8_string_lossy(),
+            "is_vaa_processed"
+        );
+        assert_eq!(invoke.args.to_vec(), args);
     }
 }
@@ -1,18 +1,85 @@
 use anyhow::{Context, Result};
 use bridge_shared::{ChainId, VAA};
+use clap::{Parser, Subcommand, ValueEnum};
 use ed25519_dalek::{Signer, SigningKey};
 use serde::{Deserialize, Serialize};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use tokio::time::{sleep, Duration};
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
+mod checkpoint;
 mod config;
+mod dlq;
+mod finality;
 mod horizon_client;
 mod near_client;
+mod outbox;
+mod supply;
 
+use checkpoint::{CheckpointBackend, CheckpointStore, FileCheckpointStore, SledCheckpointStore};
 use config::Config;
+use dlq::{DeadLetter, DeadLetterQueue};
+use finality::{FinalityPolicy, PendingObservations};
 use horizon_client::HorizonClient;
 use near_client::NearClient;
+use outbox::{MessageState, Outbox, OutboxEntry, RetryPolicy};
+use supply::{SupplyCheckMode, SupplyReport};
+
+#[derive(Parser)]
+#[command(about = "Stellar <-> NEAR bridge relayer")]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Forget the saved cursor of a chain and poll it from the default start
+    #[arg(long, value_enum, value_name = "CHAIN")]
+    reset_cursor: Vec<Chain>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Chain {
+    Stellar,
+    Near,
+}
+
+impl From<Chain> for ChainId {
+    fn from(chain: Chain) -> Self {
+        match chain {
+            Chain::Stellar => ChainId::Stellar,
+            Chain::Near => ChainId::Near,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Relay transfers between the chains (default)
+    Run,
+    /// Compare NEAR wrapped supply with Stellar locks once and exit
+    CheckSupply,
+    /// Inspect and re-drive transfers the relayer gave up on
+    ///
+    /// The database allows one process at a time; stop the relayer first.
+    Dlq {
+        #[command(subcommand)]
+        command: DlqCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum DlqCommand {
+    /// List dead letters
+    List,
+    /// Show a dead letter with its outbox entry
+    Show { origin: Chain, nonce: u64 },
+    /// Requeue a transfer for signing and delivery
+    Retry { origin: Chain, nonce: u64 },
+    /// Forget a dead letter; the transfer stays failed in the outbox
+    Drop { origin: Chain, nonce: u64 },
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LockEvent {
@@ -24,6 +91,23 @@ pub struct LockEvent {
     pub recipient: [u8; 32],
     pub nonce: u64,
     pub timestamp: u64,
+    /// Ledger (Stellar) or block height (NEAR) holding the event
+    pub height: u64,
+}
+
+/// 32-byte VAA asset id from the hex asset of an event
+fn asset_id(event: &LockEvent) -> Result<[u8; 32]> {
+    hex::decode(&event.asset)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .with_context(|| format!("Invalid asset id {:?}", event.asset))
+}
+
+/// Trees of the relayer database
+struct Stores {
+    pending: PendingObservations,
+    outbox: Outbox,
+    dlq: DeadLetterQueue,
 }
 
 pub struct Relayer {
@@ -31,6 +115,26 @@ pub struct Relayer {
     guardian_key: SigningKey,
     horizon_client: HorizonClient,
     near_client: NearClient,
+    checkpoints: Box<dyn CheckpointStore>,
+    /// Database at `{DATA_DIR}/db`, opened on first use
+    ///
+    /// sled holds an exclusive lock on it, so commands that do not need it
+    /// (`check-supply`, file checkpoints) leave it closed.
+    db_path: PathBuf,
+    db: OnceLock<sled::Db>,
+    stores: OnceLock<Stores>,
+    retry: RetryPolicy,
+    finality: FinalityPolicy,
+}
+
+fn open_db(path: &Path) -> Result<sled::Db> {
+    sled::open(path).with_context(|| format!("Failed to open database {}", path.display()))
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
 }
 
 impl Relayer {
@@ -43,35 +147,106 @@ impl Relayer {
                 .map_err(|_| anyhow::anyhow!("Invalid key length"))?
         );
 
-        let horizon_client = HorizonClient::new(&config.stellar_horizon_url);
+        let horizon_client =
+            HorizonClient::new(&config.stellar_horizon_url, &config.soroban_rpc_url);
         let near_client = NearClient::new(&config.near_rpc_url);
 
+        let data_dir = Path::new(&config.data_dir);
+        std::fs::create_dir_all(data_dir)
+            .with_context(|| format!("Failed to create {}", data_dir.display()))?;
+        let db_path = data_dir.join("db");
+        let db = OnceLock::new();
+        let checkpoints: Box<dyn CheckpointStore> = match config.checkpoint_backend {
+            CheckpointBackend::File => {
+                Box::new(FileCheckpointStore::open(data_dir.join("checkpoints.json"))?)
+            }
+            CheckpointBackend::Sled => {
+                let opened = open_db(&db_path)?;
+                Box::new(SledCheckpointStore::open(db.get_or_init(|| opened))?)
+            }
+        };
+        let retry = RetryPolicy {
+            base_delay_secs: config.retry_base_delay_secs,
+            max_delay_secs: config.retry_max_delay_secs,
+            max_attempts: config.max_delivery_attempts,
+        };
+        let finality = FinalityPolicy {
+            near_finality: config.near_finality,
+            stellar_depth: config.stellar_confirmation_depth,
+        };
+
         Ok(Self {
             config,
             guardian_key,
             horizon_client,
             near_client,
+            checkpoints,
+            db_path,
+            db,
+            stores: OnceLock::new(),
+            retry,
+            finality,
         })
     }
 
+    /// Pending observations, outbox and dead letters, opening the database
+    /// if this is the first use
+    fn stores(&self) -> Result<&Stores> {
+        if let Some(stores) = self.stores.get() {
+            return Ok(stores);
+        }
+
+        let db = match self.db.get() {
+            Some(db) => db,
+            None => {
+                let opened = open_db(&self.db_path)?;
+                self.db.get_or_init(|| opened)
+            }
+        };
+        let stores = Stores {
+            pending: PendingObservations::open(db)?,
+            outbox: Outbox::open(db)?,
+            dlq: DeadLetterQueue::open(db)?,
+        };
+        Ok(self.stores.get_or_init(|| stores))
+    }
+
+    /// Drop the saved cursor of `chain`
+    pub fn reset_cursor(&self, chain: ChainId) -> Result<()> {
+        self.checkpoints.reset(chain)?;
+        warn!("Reset {:?} cursor; polling restarts from the default", chain);
+        Ok(())
+    }
+
     pub async fn run(&self) -> Result<()> {
         info!("Starting bridge relayer...");
         info!("Monitoring Stellar bridge: {}", self.config.stellar_bridge_contract);
         info!("Monitoring NEAR bridge: {}", self.config.near_bridge_contract);
 
-        let mut last_stellar_cursor = "now".to_string();
-        let mut last_near_block = 0u64;
+        let mut last_stellar_cursor = self
+            .checkpoints
+            .load(ChainId::Stellar)?
+            .unwrap_or_else(|| "now".to_string());
+        let mut last_near_sequence = match self.checkpoints.load(ChainId::Near)? {
+            Some(sequence) => sequence.parse().context("Invalid NEAR checkpoint")?,
+            None => 0u64,
+        };
+        info!(
+            "Resuming from Stellar cursor {} and NEAR burn {}",
+            last_stellar_cursor, last_near_sequence
+        );
+        let mut last_supply_check: Option<Instant> = None;
 
         loop {
-            // Poll Stellar for lock events
-            match self
-                .poll_stellar_locks(&mut last_stellar_cursor)
-                .await
-            {
-                Ok(count) => {
+            // Poll Stellar for lock events; the cursor moves only once the
+            // whole batch is held
+            match self.poll_stellar_locks(&last_stellar_cursor).await {
+                Ok((count, next_cursor)) => {
                     if count > 0 {
                         info!("Processed {} Stellar lock events", count);
                     }
+                    self.checkpoints.save(ChainId::Stellar, &next_cursor)?;
+                    last_stellar_cursor = next_cursor;
                 }
                 Err(e) => {
                     error!("Error polling Stellar: {}", e);
@@ -79,96 +254,386 @@ impl Relayer {
             }
 
             // Poll NEAR for burn events
-            match self.poll_near_burns(&mut last_near_block).await {
-                Ok(count) => {
+            match self.poll_near_burns(last_near_sequence).await {
+                Ok((count, next_sequence)) => {
                     if count > 0 {
                         info!("Processed {} NEAR burn events", count);
                     }
+                    self.checkpoints
+                        .save(ChainId::Near, &next_sequence.to_string())?;
+                    last_near_sequence = next_sequence;
                 }
                 Err(e) => {
                     error!("Error polling NEAR: {}", e);
                 }
             }
 
+            // Queue observations that have reached finality
+            for chain in [ChainId::Stellar, ChainId::Near] {
+                match self.promote_final(chain).await {
+                    Ok(count) => {
+                        if count > 0 {
+                            info!("{} {:?} events reached finality", count, chain);
+                        }
+                    }
+                    Err(e) => {
+                        error!("Error checking {:?} finality: {}", chain, e);
+                    }
+                }
+            }
+
+            // Sign and deliver everything due in the outbox
+            match self.process_outbox().await {
+                Ok(count) => {
+                    if count > 0 {
+                        info!("Delivered {} VAAs", count);
+                    }
+                }
+                Err(e) => {
+                    error!("Error processing outbox: {}", e);
+                }
+            }
+
+            // Check the supply invariant every supply_check_interval_secs
+            let check_interval = Duration::from_secs(self.config.supply_check_interval_secs);
+            if last_supply_check.is_none_or(|at| at.elapsed() >= check_interval) {
+                if let Err(e) = self.check_supply().await {
+                    error!("Error checking supply: {}", e);
+                }
+                last_supply_check = Some(Instant::now());
+            }
+
             // Sleep before next poll
             sleep(Duration::from_secs(self.config.poll_interval_secs)).await;
         }
     }
 
-    async fn poll_stellar_locks(&self, cursor: &mut String) -> Result<usize> {
-        let events = self
+    /// Compare wrapped supply on NEAR with outstanding locks on Stellar
+    ///
+    /// NEAR supply above the Stellar locks means wrapped tokens are not
+    /// backed; depending on `supply_check_mode` this is logged or alerted
+    /// on. Stellar locks above NEAR supply are expected while transfers are
+    /// in flight.
+    pub async fn check_supply(&self) -> Result<Vec<SupplyReport>> {
+        let near: BTreeMap<String, u128> = self
+            .near_client
+            .get_wrapped_supplies(&self.config.near_bridge_contract)
+            .await?
+            .into_iter()
+            .collect();
+
+        let mut stellar = BTreeMap::new();
+        for asset_id in near.keys() {
+            let outstanding = self
+                .horizon_client
+                .get_outstanding_locks(&self.config.stellar_bridge_contract, asset_id)
+                .await?;
+            stellar.insert(asset_id.clone(), outstanding);
+        }
+
+        let reports = supply::compare(&near, &stellar);
+        for report in reports.iter().filter(|report| !report.is_balanced()) {
+            if !report.is_unbacked() {
+                info!(
+                    "Supply of {} lags locks: NEAR {} < Stellar {}",
+                    report.asset_id, report.near_supply, report.stellar_outstanding
+                );
+                continue;
+            }
+
+            error!(
+                "Unbacked supply of {}: NEAR {} > Stellar {}",
+                report.asset_id, report.near_supply, report.stellar_outstanding
+            );
+            if self.config.supply_check_mode == SupplyCheckMode::Report {
+                continue;
+            }
+            if let Err(e) = self.send_alert(report).await {
+                error!("Failed to send supply alert: {}", e);
+            }
+        }
+
+        Ok(reports)
+    }
+
+    async fn send_alert(&self, report: &SupplyReport) -> Result<()> {
+        let Some(url) = &self.config.alert_webhook_url else {
+            warn!("ALERT_WEBHOOK_URL not set; supply alert only logged");
+            return Ok(());
+        };
+
+        reqwest::Client::new()
+            .post(url)
+            .json(&serde_json::json!({ "alert": "unbacked_supply", "report": report }))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    /// Hold the lock events after `cursor`; returns how many were new and
+    /// the cursor to continue from
+    async fn poll_stellar_locks(&self, cursor: &str) -> Result<(usize, String)> {
+        let (events, next_cursor) = self
             .horizon_client
             .get_contract_events(&self.config.stellar_bridge_contract, cursor)
             .await?;
 
+        let pending = &self.stores()?.pending;
         let mut count = 0;
         for event in events {
             if event.event_type == "lock" {
                 info!("Detected Stellar lock event: nonce={}", event.nonce);
 
-                // Create VAA
-                let vaa = self.create_vaa_from_stellar_lock(&event)?;
-
-                // Submit to NEAR
-                match self.submit_vaa_to_near(&vaa).await {
-                    Ok(_) => {
-                        info!("Successfully submitted VAA to NEAR for nonce {}", event.nonce);
-                        count += 1;
-                    }
-                    Err(e) => {
-                        error!("Failed to submit VAA to NEAR: {}", e);
-                    }
+                // Hold until final, then sign and deliver to NEAR
+                let entry = OutboxEntry::observed(ChainId::Stellar, ChainId::Near, event);
+                if pending.hold(&entry)? {
+                    count += 1;
                 }
             }
         }
 
-        Ok(count)
+        Ok((count, next_cursor))
     }
 
-    async fn poll_near_burns(&self, last_block: &mut u64) -> Result<usize> {
-        let events = self
+    /// Hold the burns after sequence `last_sequence`; returns how many were
+    /// new and the sequence to continue from
+    async fn poll_near_burns(&self, last_sequence: u64) -> Result<(usize, u64)> {
+        let (events, next_sequence) = self
             .near_client
-            .get_burn_events(&self.config.near_bridge_contract, *last_block)
+            .get_burn_events(
+                &self.config.near_bridge_contract,
+                last_sequence,
+                self.finality.near_finality,
+            )
             .await?;
 
+        let pending = &self.stores()?.pending;
         let mut count = 0;
         for event in events {
-            info!("Detected NEAR burn event");
+            info!("Detected NEAR burn event: nonce={}", event.nonce);
 
-            // Create VAA
-            let vaa = self.create_vaa_from_near_burn(&event)?;
+            // Hold until final, then sign and deliver to Stellar
+            let entry = OutboxEntry::observed(ChainId::Near, ChainId::Stellar, event);
+            if pending.hold(&entry)? {
+                count += 1;
+            }
+        }
 
-            // Submit to Stellar
-            match self.submit_vaa_to_stellar(&vaa).await {
-                Ok(_) => {
-                    info!("Successfully submitted VAA to Stellar");
-                    count += 1;
-                }
-                Err(e) => {
-                    error!("Failed to submit VAA to Stellar: {}", e);
+        Ok((count, next_sequence))
+    }
+
+    /// Move held observations from `chain` that reached finality to the outbox
+    ///
+    /// Nothing is signed before this point, so guardians never attest to an
+    /// event that a reorganisation could still remove.
+    async fn promote_final(&self, chain: ChainId) -> Result<usize> {
+        let stores = self.stores()?;
+        let held = stores.pending.by_origin(chain)?;
+        if held.is_empty() {
+            return Ok(0);
+        }
+
+        let head = match chain {
+            ChainId::Stellar => self.horizon_client.get_latest_ledger().await?,
+            ChainId::Near => {
+                self.near_client
+                    .get_block_height(self.finality.near_finality)
+                    .await?
+            }
+        };
+
+        let mut count = 0;
+        for entry in held {
+            if !self.finality.is_final(chain, entry.event.height, head) {
+                continue;
+            }
+            let nonce = entry.nonce();
+            // Queued before removal: a crash in between only repeats the no-op observe
+            stores.outbox.observe(entry)?;
+            stores.pending.remove(chain, nonce)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Work through the outbox entries whose retry time has come
+    ///
+    /// Returns how many were confirmed by their destination.
+    async fn process_outbox(&self) -> Result<usize> {
+        let mut confirmed = 0;
+        for entry in self.stores()?.outbox.due(unix_now())? {
+            let (origin, nonce) = (entry.origin_chain, entry.nonce());
+            match self.deliver(entry).await {
+                Ok(MessageState::Confirmed) => confirmed += 1,
+                Ok(_) => {}
+                Err(e) => error!("Failed to deliver {:?} nonce {}: {}", origin, nonce, e),
+            }
+        }
+        Ok(confirmed)
+    }
+
+    /// Move one entry as far along as it goes and return its new state
+    ///
+    /// Each VAA is signed once and the signed copy is stored, so redeliveries
+    /// send identical bytes. Before every submission the destination is asked
+    /// whether it already processed the nonce; an entry whose earlier
+    /// submission cannot be checked is not sent again blindly.
+    async fn deliver(&self, mut entry: OutboxEntry) -> Result<MessageState> {
+        let outbox = &self.stores()?.outbox;
+        let nonce = entry.nonce();
+        if entry.state == MessageState::Observed {
+            let signed = match entry.origin_chain {
+                ChainId::Stellar => self.create_vaa_from_stellar_lock(&entry.event),
+                ChainId::Near => self.create_vaa_from_near_burn(&entry.event),
+            };
+            let vaa = match signed {
+                Ok(vaa) => vaa,
+                Err(e) => return self.dead_letter(entry, format!("Signing failed: {:#}", e)),
+            };
+            entry.vaa = Some(vaa);
+            entry.state = MessageState::Signed;
+            outbox.update(&entry)?;
+        }
+
+        match self.is_delivered(&entry).await {
+            Ok(true) => {
+                info!(
+                    "{:?} nonce {} confirmed on {:?}",
+                    entry.origin_chain, nonce, entry.destination_chain
+                );
+                entry.state = MessageState::Confirmed;
+                entry.last_error = None;
+                outbox.update(&entry)?;
+                return Ok(entry.state);
+            }
+            Ok(false) => {}
+            Err(e) if entry.state == MessageState::Submitted => {
+                // Counts as an attempt so that an unreachable check still ends
+                // in the dead-letter queue
+                entry.attempts += 1;
+                if entry.attempts >= self.retry.max_attempts {
+                    let reason = format!(
+                        "Delivery not confirmed after {} attempts: {:#}",
+                        entry.attempts, e
+                    );
+                    return self.dead_letter(entry, reason);
                 }
+                warn!("Cannot check delivery of nonce {}; retrying later: {}", nonce, e);
+                entry.last_error = Some(e.to_string());
+                entry.next_attempt_at = unix_now() + self.retry.backoff(entry.attempts);
+                outbox.update(&entry)?;
+                return Ok(entry.state);
             }
+            Err(e) => warn!("Cannot check delivery of nonce {}; submitting: {}", nonce, e),
         }
 
-        Ok(count)
+        if entry.attempts >= self.retry.max_attempts {
+            let reason = match entry.last_error.take() {
+                Some(e) => format!("Gave up after {} attempts: {}", entry.attempts, e),
+                None => format!("Not confirmed after {} attempts", entry.attempts),
+            };
+            return self.dead_letter(entry, reason);
+        }
+
+        entry.attempts += 1;
+        let vaa = entry.vaa.as_ref().context("Signed outbox entry without a VAA")?;
+        let submitted = match entry.destination_chain {
+            ChainId::Near => self.submit_vaa_to_near(vaa).await,
+            ChainId::Stellar => self.submit_vaa_to_stellar(vaa).await,
+        };
+        match submitted {
+            Ok(()) => {
+                info!("Submitted VAA for nonce {} to {:?}", nonce, entry.destination_chain);
+                entry.state = MessageState::Submitted;
+                entry.last_error = None;
+            }
+            Err(e) => {
+                error!("Failed to submit VAA for nonce {}: {}", nonce, e);
+                entry.last_error = Some(e.to_string());
+            }
+        }
+        entry.next_attempt_at = unix_now() + self.retry.backoff(entry.attempts);
+        outbox.update(&entry)?;
+        Ok(entry.state)
     }
 
-    fn create_vaa_from_stellar_lock(&self, event: &LockEvent) -> Result<VAA> {
-        let origin_contract = hex::decode(&self.config.stellar_bridge_contract)
-            .context("Invalid Stellar contract address")?;
-        let destination_contract = hex::decode(&self.config.near_bridge_contract)
-            .context("Invalid NEAR contract address")?;
+    /// Mark an entry failed and record it in the dead-letter queue
+    fn dead_letter(&self, mut entry: OutboxEntry, reason: String) -> Result<MessageState> {
+        let stores = self.stores()?;
+        error!("Dead-lettered {:?} nonce {}: {}", entry.origin_chain, entry.nonce(), reason);
+        let letter = DeadLetter {
+            origin_chain: entry.origin_chain,
+            nonce: entry.nonce(),
+            reason: reason.clone(),
+            attempts: entry.attempts,
+            failed_at: unix_now(),
+        };
+        entry.state = MessageState::Failed;
+        entry.last_error = Some(reason);
+        stores
+            .outbox
+            .update_with_letter(&entry, &stores.dlq, Some(&letter))?;
+        Ok(entry.state)
+    }
+
+    /// Move a dead letter back into the outbox to be signed afresh
+    ///
+    /// The VAA is signed again so that corrected configuration takes effect;
+    /// delivery still checks whether the destination processed the nonce.
+    pub fn retry_dead_letter(&self, origin_chain: ChainId, nonce: u64) -> Result<()> {
+        let stores = self.stores()?;
+        let mut entry = stores
+            .outbox
+            .get(origin_chain, nonce)?
+            .context("Transfer is not in the outbox")?;
+        if stores.dlq.get(origin_chain, nonce)?.is_none() {
+            anyhow::bail!("{:?} nonce {} is not dead-lettered", origin_chain, nonce);
+        }
+
+        entry.vaa = None;
+        entry.state = MessageState::Observed;
+        entry.attempts = 0;
+        entry.next_attempt_at = 0;
+        entry.last_error = None;
+        stores
+            .outbox
+            .update_with_letter(&entry, &stores.dlq, None)?;
+        Ok(())
+    }
+
+    /// Ask the destination whether it already processed the entry's nonce
+    async fn is_delivered(&self, entry: &OutboxEntry) -> Result<bool> {
+        match entry.destination_chain {
+            ChainId::Near => {
+                self.near_client
+                    .is_vaa_processed(&self.config.near_bridge_contract, entry.nonce())
+                    .await
+            }
+            ChainId::Stellar => {
+                // Stellar-bound entries are NEAR burns, settled by releases
+                self.horizon_client
+                    .is_burn_released(&self.config.stellar_bridge_contract, entry.nonce())
+                    .await
+            }
+        }
+    }
 
-        // Parse asset ID
-        let asset_bytes = hex::decode(&event.asset).unwrap_or_else(|_| vec![0u8; 32]);
-        let mut asset_id = [0u8; 32];
-        asset_id[..asset_bytes.len().min(32)].copy_from_slice(&asset_bytes[..asset_bytes.len().min(32)]);
+    /// VAA addresses of the Stellar and NEAR bridge contracts
+    ///
+    /// The NEAR bridge checks `destination_contract` against the hash of its
+    /// account id.
+    fn bridge_addresses(&self) -> Result<([u8; 32], [u8; 32])> {
+        let stellar = horizon_client::contract_id(&self.config.stellar_bridge_contract)
+            .context("Invalid Stellar contract address")?;
+        let near = bridge_shared::contract_address(&self.config.near_bridge_contract);
+        Ok((stellar, near))
+    }
 
-        let mut origin = [0u8; 32];
-        origin[..origin_contract.len().min(32)].copy_from_slice(&origin_contract[..origin_contract.len().min(32)]);
+    fn create_vaa_from_stellar_lock(&self, event: &LockEvent) -> Result<VAA> {
+        let (origin, destination) = self.bridge_addresses()?;
 
-        let mut destination = [0u8; 32];
-        destination[..destination_contract.len().min(32)].copy_from_slice(&destination_contract[..destination_contract.len().min(32)]);
+        let asset_id = asset_id(event)?;
 
         let mut vaa = VAA::new(
             ChainId::Stellar,
@@ -194,24 +659,9 @@ impl Relayer {
 
     fn create_vaa_from_near_burn(&self, event: &LockEvent) -> Result<VAA> {
         // Similar to stellar lock but reversed direction
-        let origin_contract = hex::decode(&self.config.near_bridge_contract)
-            .context("Invalid NEAR contract address")?;
-        let destination_contract = hex::decode(&self.config.stellar_bridge_contract)
-            .context("Invalid Stellar contract address")?;
-
-        let mut asset_id = [0u8; 32];
-        let asset_bytes = event.asset.as_bytes();
-        asset_id[..asset_bytes.len().min(32)].copy_from_slice(&asset_bytes[..asset_bytes.len().min(32)]);
+        let (destination, origin) = self.bridge_addresses()?;
 
-        let mut origin = [0u8; 32];
-        origin[..origin_contract.len().min(32)].copy_from_slice(&origin_contract[..origin_contract.len().min(32)]);
-
-        let mut destination = [0u8; 32];
-        destination[..destination_contract.len().min(32)].copy_from_slice(&destination_contract[..destination_contract.len().min(32)]);
-
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)?
-            .as_secs();
+        let asset_id = asset_id(event)?;
 
         let mut vaa = VAA::new(
             ChainId::Near,
@@ -222,7 +672,9 @@ impl Relayer {
             event.amount,
             event.recipient,
             event.nonce,
-            timestamp,
+            // The burn's block timestamp, so every guardian and every re-sign
+            // produces the same digest
+            event.timestamp,
         );
 
         // Sign
@@ -255,11 +707,69 @@ async fn main() -> Result<()> {
     // Initialize logging
     tracing_subscriber::fmt::init();
 
+    let cli = Cli::parse();
+
     // Load config
     dotenv::dotenv().ok();
     let config = Config::from_env()?;
 
     // Create and run relayer
     let relayer = Relayer::new(config)?;
-    relayer.run().await
+    for chain in cli.reset_cursor {
+        relayer.reset_cursor(chain.into())?;
+    }
+    match cli.command.unwrap_or(Command::Run) {
+        Command::Run => relayer.run().await,
+        Command::Dlq { command } => run_dlq_command(&relayer, command),
+        Command::CheckSupply => {
+            let reports = relayer.check_supply().await?;
+            for report in &reports {
+                println!(
+                    "{} near={} stellar={} {}",
+                    report.asset_id,
+                    report.near_supply,
+                    report.stellar_outstanding,
+                    if report.is_balanced() { "ok" } else { "MISMATCH" }
+                );
+            }
+            Ok(())
+        }
+    }
+}
+
+fn run_dlq_command(relayer: &Relayer, command: DlqCommand) -> Result<()> {
+    let stores = relayer.stores()?;
+    match command {
+        DlqCommand::List => {
+            for letter in stores.dlq.list()? {
+                println!(
+                    "{:?} {} attempts={} failed_at={} {}",
+                    letter.origin_chain, letter.nonce, letter.attempts, letter.failed_at,
+                    letter.reason
+                );
+            }
+        }
+        DlqCommand::Show { origin, nonce } => {
+            let origin = ChainId::from(origin);
+            let letter = stores
+                .dlq
+                .get(origin, nonce)?
+                .context("No such dead letter")?;
+            let entry = stores.outbox.get(origin, nonce)?;
+            let shown = serde_json::json!({ "dead_letter": letter, "outbox_entry": entry });
+            println!("{}", serde_json::to_string_pretty(&shown)?);
+        }
+        DlqCommand::Retry { origin, nonce } => {
+            relayer.retry_dead_letter(origin.into(), nonce)?;
+            println!("Requeued {:?} nonce {}", ChainId::from(origin), nonce);
+        }
+        DlqCommand::Drop { origin, nonce } => {
+            stores
+                .dlq
+                .remove(origin.into(), nonce)?
+                .context("No such dead letter")?;
+            println!("Dropped {:?} nonce {}", ChainId::from(origin), nonce);
+        }
+    }
+    Ok(())
 }
@@ -1,8 +1,61 @@
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
 use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
 
+use crate::finality::NearFinality;
 use crate::LockEvent;
 
+/// Page size for the bridge list views (the contract's `MAX_PAGE_SIZE`)
+const PAGE_SIZE: u32 = 100;
+
+/// Entry of the bridge `list_assets` view
+#[derive(Deserialize)]
+struct AssetView {
+    token: WrappedToken,
+    total_supply: u128,
+}
+
+#[derive(Deserialize)]
+struct WrappedToken {
+    asset_id: String,
+}
+
+/// Entry of the bridge `list_outbound` view
+#[derive(Deserialize)]
+struct OutboundMessage {
+    sequence: u64,
+    asset_id: String,
+    amount: u128,
+    sender: String,
+    recipient_chain: u8,
+    recipient_address: String,
+    timestamp: u64,
+    block_height: u64,
+}
+
+impl OutboundMessage {
+    fn into_event(self) -> Result<LockEvent> {
+        let recipient = hex::decode(&self.recipient_address)?
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("Burn {} recipient is not 32 bytes", self.sequence))?;
+        Ok(LockEvent {
+            event_type: "burn".to_string(),
+            asset: self.asset_id,
+            amount: self.amount,
+            sender: self.sender,
+            recipient_chain: self.recipient_chain,
+            recipient,
+            nonce: self.sequence,
+            timestamp: self.timestamp,
+            height: self.block_height,
+        })
+    }
+}
+
 pub struct NearClient {
     client: Client,
     rpc_url: String,
@@ -16,40 +69,139 @@ impl NearClient {
         }
     }
 
+    /// Burns recorded after sequence `after` as seen at `finality`, and the
+    /// sequence to continue from
+    ///
+    /// Each event carries the height of the block that recorded the burn.
     pub async fn get_burn_events(
         &self,
         contract_id: &str,
-        _from_block: u64,
-    ) -> Result<Vec<LockEvent>> {
-        // Query NEAR RPC for burn events
-        // In production: use near-jsonrpc-client properly
-        
-        let _request = serde_json::json!({
+        after: u64,
+        finality: NearFinality,
+    ) -> Result<(Vec<LockEvent>, u64)> {
+        let messages: Vec<OutboundMessage> = self
+            .view_at(
+                contract_id,
+                "list_outbound",
+                serde_json::json!({ "from_sequence": after + 1, "limit": PAGE_SIZE }),
+                finality.as_str(),
+            )
+            .await?;
+
+        let next = messages.last().map_or(after, |message| message.sequence);
+        let events = messages
+            .into_iter()
+            .map(OutboundMessage::into_event)
+            .collect::<Result<_>>()?;
+        Ok((events, next))
+    }
+
+    /// Height of the latest block at `finality`
+    pub async fn get_block_height(&self, finality: NearFinality) -> Result<u64> {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": "dontcare",
+            "method": "block",
+            "params": { "finality": finality.as_str() }
+        });
+
+        let response: Value = self
+            .client
+            .post(&self.rpc_url)
+            .json(&request)
+            .send()
+            .await?
+            .json()
+            .await?;
+        if let Some(error) = response.get("error") {
+            bail!("NEAR block query failed: {}", error);
+        }
+        response["result"]["header"]["height"]
+            .as_u64()
+            .context("Unexpected response to NEAR block query")
+    }
+
+    /// Call a view method at `final` finality and decode its JSON result
+    pub async fn view<T: DeserializeOwned>(
+        &self,
+        contract_id: &str,
+        method: &str,
+        args: Value,
+    ) -> Result<T> {
+        self.view_at(contract_id, method, args, "final").await
+    }
+
+    /// Call a view method at `finality` and decode its JSON result
+    async fn view_at<T: DeserializeOwned>(
+        &self,
+        contract_id: &str,
+        method: &str,
+        args: Value,
+        finality: &str,
+    ) -> Result<T> {
+        let request = serde_json::json!({
             "jsonrpc": "2.0",
             "id": "dontcare",
             "method": "query",
             "params": {
-                "request_type": "view_account",
-                "finality": "final",
-                "account_id": contract_id
+                "request_type": "call_function",
+                "finality": finality,
+                "account_id": contract_id,
+                "method_name": method,
+                "args_base64": BASE64.encode(args.to_string()),
             }
         });
 
-        // Placeholder
-        let events = vec![];
-        Ok(events)
+        let response: Value = self
+            .client
+            .post(&self.rpc_url)
+            .json(&request)
+            .send()
+            .await?
+            .json()
+            .await?;
+        if let Some(error) = response.get("error") {
+            bail!("NEAR view {} failed: {}", method, error);
+        }
+
+        let bytes: Vec<u8> = serde_json::from_value(response["result"]["result"].clone())
+            .with_context(|| format!("Unexpected response to NEAR view {}", method))?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    /// `wrapped_supply` of every asset with a wrapped token
+    pub async fn get_wrapped_supplies(&self, contract_id: &str) -> Result<Vec<(String, u128)>> {
+        let mut supplies = Vec::new();
+        loop {
+            let args = serde_json::json!({ "cursor": supplies.len(), "limit": PAGE_SIZE });
+            let page: Vec<AssetView> = self.view(contract_id, "list_assets", args).await?;
+            let done = page.len() < PAGE_SIZE as usize;
+            supplies.extend(
+                page.into_iter()
+                    .map(|asset| (asset.token.asset_id, asset.total_supply)),
+            );
+            if done {
+                return Ok(supplies);
+            }
+        }
+    }
+
+    /// Whether the bridge has already minted for `nonce`
+    pub async fn is_vaa_processed(&self, contract_id: &str, nonce: u64) -> Result<bool> {
+        self.view(contract_id, "is_vaa_processed", serde_json::json!({ "nonce": nonce }))
+            .await
     }
 
+    /// Submit a `mint_wrapped` call carrying `vaa_json`
+    ///
+    /// Not implemented: the relayer holds no NEAR signing key yet. Failing
+    /// keeps the outbox entry signed and retryable instead of marking a mint
+    /// that was never sent as submitted.
     pub async fn call_mint_wrapped(&self, contract_id: &str, vaa_json: String) -> Result<()> {
-        // Call mint_wrapped on NEAR contract
-        // In production: use near-jsonrpc-client to build function call TX
-        
-        tracing::info!(
-            "Would call mint_wrapped on {} with VAA: {}",
+        bail!(
+            "mint_wrapped submission to {} is not implemented ({}-byte VAA not sent)",
             contract_id,
-            &vaa_json[..vaa_json.len().min(100)]
-        );
-
-        Ok(())
+            vaa_json.len()
+        )
     }
 }
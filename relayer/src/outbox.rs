@@ -0,0 +1,354 @@
+use anyhow::{Context, Result};
+use bridge_shared::{ChainId, VAA};
+use serde::{Deserialize, Serialize};
+use sled::transaction::{
+    ConflictableTransactionError, TransactionError, Transactional, TransactionalTree,
+};
+
+use crate::dlq::{DeadLetter, DeadLetterQueue};
+use crate::LockEvent;
+
+/// Delivery progress of one transfer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageState {
+    /// Event seen on the origin chain, not yet signed
+    Observed,
+    /// VAA signed, not yet sent to the destination
+    Signed,
+    /// VAA sent; waiting for the destination to report it processed
+    Submitted,
+    /// Destination reports the VAA processed
+    Confirmed,
+    /// Given up on; the reason is in the dead-letter queue
+    Failed,
+}
+
+impl MessageState {
+    /// Whether the relayer still has work to do for this message
+    pub fn is_pending(self) -> bool {
+        matches!(
+            self,
+            MessageState::Observed | MessageState::Signed | MessageState::Submitted
+        )
+    }
+}
+
+/// A transfer on its way from `origin_chain` to `destination_chain`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboxEntry {
+    pub origin_chain: ChainId,
+    pub destination_chain: ChainId,
+    pub event: LockEvent,
+    pub vaa: Option<VAA>,
+    pub state: MessageState,
+    /// Deliveries and failed delivery checks so far
+    pub attempts: u32,
+    /// Unix time (seconds) before which the entry is left alone
+    pub next_attempt_at: u64,
+    pub last_error: Option<String>,
+}
+
+impl OutboxEntry {
+    pub fn observed(origin_chain: ChainId, destination_chain: ChainId, event: LockEvent) -> Self {
+        Self {
+            origin_chain,
+            destination_chain,
+            event,
+            vaa: None,
+            state: MessageState::Observed,
+            attempts: 0,
+            next_attempt_at: 0,
+            last_error: None,
+        }
+    }
+
+    pub fn nonce(&self) -> u64 {
+        self.event.nonce
+    }
+}
+
+/// Retry timing for deliveries
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub base_delay_secs: u64,
+    pub max_delay_secs: u64,
+    pub max_attempts: u32,
+}
+
+impl RetryPolicy {
+    /// Delay after the `attempts`-th delivery: doubles each time up to the cap
+    pub fn backoff(&self, attempts: u32) -> u64 {
+        let factor = 1u64
+            .checked_shl(attempts.saturating_sub(1))
+            .unwrap_or(u64::MAX);
+        self.base_delay_secs
+            .saturating_mul(factor)
+            .min(self.max_delay_secs)
+    }
+}
+
+/// Signed VAAs awaiting delivery, kept in the `outbox` tree of the database
+///
+/// Entries are keyed by origin chain and nonce. Pending entries are also
+/// indexed by retry time in `outbox_due`, so a poll only reads the entries
+/// that are due. Confirmed entries are removed, leaving only their key in
+/// `outbox_confirmed`; failed ones stay for the dead-letter queue. Observing
+/// the same event again after a restart finds the entry or its confirmed key
+/// instead of relaying twice.
+pub struct Outbox {
+    tree: sled::Tree,
+    due: sled::Tree,
+    confirmed: sled::Tree,
+}
+
+pub(crate) fn entry_key(origin_chain: ChainId, nonce: u64) -> [u8; 9] {
+    let mut key = [0u8; 9];
+    key[0] = origin_chain as u8;
+    key[1..].copy_from_slice(&nonce.to_be_bytes());
+    key
+}
+
+/// Due index key: retry time, then the entry key
+fn due_key(next_attempt_at: u64, entry_key: &[u8; 9]) -> [u8; 17] {
+    let mut key = [0u8; 17];
+    key[..8].copy_from_slice(&next_attempt_at.to_be_bytes());
+    key[8..].copy_from_slice(entry_key);
+    key
+}
+
+fn due_key_of(entry: &OutboxEntry) -> Option<[u8; 17]> {
+    let key = entry_key(entry.origin_chain, entry.nonce());
+    entry
+        .state
+        .is_pending()
+        .then(|| due_key(entry.next_attempt_at, &key))
+}
+
+type TxResult<T> = std::result::Result<T, ConflictableTransactionError<anyhow::Error>>;
+
+fn tx_error(e: TransactionError<anyhow::Error>) -> anyhow::Error {
+    match e {
+        TransactionError::Abort(e) => e,
+        TransactionError::Storage(e) => e.into(),
+    }
+}
+
+/// Write `entry` (serialized as `value`) within a transaction over the
+/// outbox trees, moving its due index key and confirming it if need be
+fn write_entry(
+    (tree, due, confirmed): (&TransactionalTree, &TransactionalTree, &TransactionalTree),
+    entry: &OutboxEntry,
+    value: &[u8],
+) -> TxResult<()> {
+    let key = entry_key(entry.origin_chain, entry.nonce());
+    if let Some(old) = tree.get(key)? {
+        let old: OutboxEntry = serde_json::from_slice(&old)
+            .context("Corrupt outbox entry")
+            .map_err(ConflictableTransactionError::Abort)?;
+        if let Some(old_due_key) = due_key_of(&old) {
+            due.remove(&old_due_key[..])?;
+        }
+    }
+
+    if entry.state == MessageState::Confirmed {
+        tree.remove(&key[..])?;
+        confirmed.insert(&key[..], &[][..])?;
+        return Ok(());
+    }
+    tree.insert(&key[..], value)?;
+    if let Some(due_key) = due_key_of(entry) {
+        due.insert(&due_key[..], &[][..])?;
+    }
+    Ok(())
+}
+
+impl Outbox {
+    pub fn open(db: &sled::Db) -> Result<Self> {
+        Ok(Self {
+            tree: db.open_tree("outbox")?,
+            due: db.open_tree("outbox_due")?,
+            confirmed: db.open_tree("outbox_confirmed")?,
+        })
+    }
+
+    /// Record a newly observed event; `false` if it is already in the outbox
+    /// or was confirmed
+    pub fn observe(&self, entry: OutboxEntry) -> Result<bool> {
+        let key = entry_key(entry.origin_chain, entry.nonce());
+        let value = serde_json::to_vec(&entry)?;
+        let inserted = (&self.tree, &self.due, &self.confirmed)
+            .transaction(|(tree, due, confirmed)| -> TxResult<bool> {
+                if tree.get(key)?.is_some() || confirmed.get(key)?.is_some() {
+                    return Ok(false);
+                }
+                tree.insert(&key[..], value.as_slice())?;
+                if let Some(due_key) = due_key_of(&entry) {
+                    due.insert(&due_key[..], &[][..])?;
+                }
+                Ok(true)
+            })
+            .map_err(tx_error)?;
+        self.tree.flush()?;
+        Ok(inserted)
+    }
+
+    pub fn get(&self, origin_chain: ChainId, nonce: u64) -> Result<Option<OutboxEntry>> {
+        self.tree
+            .get(entry_key(origin_chain, nonce))?
+            .map(|bytes| serde_json::from_slice(&bytes).context("Corrupt outbox entry"))
+            .transpose()
+    }
+
+    /// Store the new state of an entry, or remove it once confirmed;
+    /// durable once this returns
+    pub fn update(&self, entry: &OutboxEntry) -> Result<()> {
+        let value = serde_json::to_vec(entry)?;
+        (&self.tree, &self.due, &self.confirmed)
+            .transaction(|trees| write_entry((&trees.0, &trees.1, &trees.2), entry, &value))
+            .map_err(tx_error)?;
+        self.tree.flush()?;
+        Ok(())
+    }
+
+    /// Store the new state of an entry and insert (`Some`) or remove
+    /// (`None`) its dead letter in the same transaction
+    pub fn update_with_letter(
+        &self,
+        entry: &OutboxEntry,
+        dlq: &DeadLetterQueue,
+        letter: Option<&DeadLetter>,
+    ) -> Result<()> {
+        let key = entry_key(entry.origin_chain, entry.nonce());
+        let value = serde_json::to_vec(entry)?;
+        let letter = letter.map(serde_json::to_vec).transpose()?;
+        (&self.tree, &self.due, &self.confirmed, &dlq.tree)
+            .transaction(|(tree, due, confirmed, letters)| -> TxResult<()> {
+                write_entry((tree, due, confirmed), entry, &value)?;
+                match &letter {
+                    Some(letter) => letters.insert(&key[..], letter.as_slice())?,
+                    None => letters.remove(&key[..])?,
+                };
+                Ok(())
+            })
+            .map_err(tx_error)?;
+        self.tree.flush()?;
+        Ok(())
+    }
+
+    /// Pending entries whose retry time has come, by retry time
+    pub fn due(&self, now: u64) -> Result<Vec<OutboxEntry>> {
+        let end = now.saturating_add(1).to_be_bytes();
+        let mut due = Vec::new();
+        for item in self.due.range(..&end[..]) {
+            let (due_key, _) = item?;
+            if let Some(bytes) = self.tree.get(&due_key[8..])? {
+                due.push(serde_json::from_slice(&bytes).context("Corrupt outbox entry")?);
+            }
+        }
+        Ok(due)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(nonce: u64) -> LockEvent {
+        LockEvent {
+            event_type: "lock".to_string(),
+            asset: "aa".to_string(),
+            amount: 100,
+            sender: "sender".to_string(),
+            recipient_chain: ChainId::Near as u8,
+            recipient: [7u8; 32],
+            nonce,
+            timestamp: 0,
+            height: 0,
+        }
+    }
+
+    #[test]
+    fn test_observe_is_idempotent_and_due_respects_backoff() {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        let outbox = Outbox::open(&db).unwrap();
+
+        let entry = OutboxEntry::observed(ChainId::Stellar, ChainId::Near, event(1));
+        assert!(outbox.observe(entry.clone()).unwrap());
+        assert!(!outbox.observe(entry).unwrap());
+        assert!(outbox
+            .observe(OutboxEntry::observed(
+                ChainId::Near,
+                ChainId::Stellar,
+                event(1)
+            ))
+            .unwrap());
+
+        let mut entry = outbox.due(0).unwrap().remove(0);
+        assert_eq!(entry.origin_chain, ChainId::Stellar);
+        entry.state = MessageState::Submitted;
+        entry.next_attempt_at = 50;
+        outbox.update(&entry).unwrap();
+        assert_eq!(outbox.due(10).unwrap().len(), 1);
+        assert_eq!(outbox.due(50).unwrap().len(), 2);
+
+        entry.state = MessageState::Confirmed;
+        outbox.update(&entry).unwrap();
+        assert_eq!(outbox.due(50).unwrap().len(), 1);
+        assert!(outbox.get(ChainId::Stellar, 1).unwrap().is_none());
+
+        // Confirmed ids are remembered, so a re-observed event is not relayed again
+        let again = OutboxEntry::observed(ChainId::Stellar, ChainId::Near, event(1));
+        assert!(!outbox.observe(again).unwrap());
+        assert!(outbox.get(ChainId::Stellar, 1).unwrap().is_none());
+
+        let mut entry = outbox.due(0).unwrap().remove(0);
+        entry.state = MessageState::Failed;
+        outbox.update(&entry).unwrap();
+        assert!(outbox.due(u64::MAX).unwrap().is_empty());
+        assert!(outbox.get(ChainId::Near, 1).unwrap().is_some());
+        assert!(outbox.due.is_empty());
+    }
+
+    #[test]
+    fn test_dead_letter_written_with_entry() {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        let outbox = Outbox::open(&db).unwrap();
+        let dlq = DeadLetterQueue::open(&db).unwrap();
+
+        let mut entry = OutboxEntry::observed(ChainId::Near, ChainId::Stellar, event(3));
+        outbox.observe(entry.clone()).unwrap();
+        entry.state = MessageState::Failed;
+        let letter = DeadLetter {
+            origin_chain: ChainId::Near,
+            nonce: 3,
+            reason: "rejected".to_string(),
+            attempts: 10,
+            failed_at: 100,
+        };
+        outbox
+            .update_with_letter(&entry, &dlq, Some(&letter))
+            .unwrap();
+        assert!(dlq.get(ChainId::Near, 3).unwrap().is_some());
+        assert!(outbox.due(u64::MAX).unwrap().is_empty());
+
+        entry.state = MessageState::Observed;
+        outbox.update_with_letter(&entry, &dlq, None).unwrap();
+        assert!(dlq.get(ChainId::Near, 3).unwrap().is_none());
+        assert_eq!(outbox.due(0).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_cap() {
+        let policy = RetryPolicy {
+            base_delay_secs: 5,
+            max_delay_secs: 60,
+            max_attempts: 10,
+        };
+        assert_eq!(policy.backoff(1), 5);
+        assert_eq!(policy.backoff(2), 10);
+        assert_eq!(policy.backoff(4), 40);
+        assert_eq!(policy.backoff(5), 60);
+        assert_eq!(policy.backoff(200), 60);
+    }
+}
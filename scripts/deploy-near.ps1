@@ -33,3 +33,7 @@ Write-Host "   near deploy --accountId bridge.YOUR_ACCOUNT.testnet --wasmFile $w
 Write-Host ""
 Write-Host "5. Initialize the contract:"
 Write-Host "   near call bridge.YOUR_ACCOUNT.testnet new '{""owner"":""YOUR_ACCOUNT.testnet"",""guardians"":[[1,2,3,...]],""quorum"":1}' --accountId YOUR_ACCOUNT.testnet"
+Write-Host ""
+Write-Host "6. Upload the wrapped token wasm (contracts/near-wrapped-token) and deploy a token per asset:"
+Write-Host "   near call bridge.YOUR_ACCOUNT.testnet set_token_code --base64 <WASM_BASE64> --accountId YOUR_ACCOUNT.testnet"
+Write-Host "   near call bridge.YOUR_ACCOUNT.testnet deploy_wrapped_token '{""asset_id"":""<HEX>"",""metadata"":{""name"":""USD Coin"",""symbol"":""USDC"",""icon"":null,""decimals"":7}}' --accountId YOUR_ACCOUNT.testnet --deposit 3"
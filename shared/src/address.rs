@@ -0,0 +1,143 @@
+use crate::{contract_address, ChainId};
+
+/// Strkey version byte of an ed25519 account (`G...`)
+const STRKEY_ACCOUNT: u8 = 6 << 3;
+
+/// Strkey version byte of a contract (`C...`)
+const STRKEY_CONTRACT: u8 = 2 << 3;
+
+/// Length of a 32-byte strkey: base32 of version + payload + checksum
+const STRKEY_LEN: usize = 56;
+
+/// Rejected destination chain or recipient address
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum AddressError {
+    #[error("unknown chain id {0}")]
+    UnknownChain(u8),
+
+    #[error("invalid {0:?} address")]
+    InvalidAddress(ChainId),
+}
+
+impl TryFrom<u8> for ChainId {
+    type Error = AddressError;
+
+    fn try_from(id: u8) -> Result<Self, Self::Error> {
+        match id {
+            1 => Ok(ChainId::Stellar),
+            2 => Ok(ChainId::Near),
+            _ => Err(AddressError::UnknownChain(id)),
+        }
+    }
+}
+
+/// Check a recipient address on `chain` and return its 32-byte form
+///
+/// Stellar recipients must be `G...` account or `C...` contract strkeys with
+/// a valid checksum; the 32-byte form is the key. NEAR recipients must be
+/// valid account ids; the 32-byte form is `contract_address` of the id.
+pub fn parse_address(chain: ChainId, address: &str) -> Result<[u8; 32], AddressError> {
+    let parsed = match chain {
+        ChainId::Stellar => decode_strkey(address),
+        ChainId::Near => is_near_account_id(address).then(|| contract_address(address)),
+    };
+    parsed.ok_or(AddressError::InvalidAddress(chain))
+}
+
+/// Decode a `G...` or `C...` strkey into its 32-byte payload
+fn decode_strkey(address: &str) -> Option<[u8; 32]> {
+    if address.len() != STRKEY_LEN {
+        return None;
+    }
+
+    // Unpadded RFC 4648 base32: 56 characters hold exactly 35 bytes
+    let mut bytes = Vec::with_capacity(35);
+    let (mut buffer, mut bits) = (0u32, 0);
+    for c in address.bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'2'..=b'7' => c - b'2' + 26,
+            _ => return None,
+        };
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+
+    let (data, checksum) = bytes.split_at(33);
+    if ![STRKEY_ACCOUNT, STRKEY_CONTRACT].contains(&data[0])
+        || crc16_xmodem(data).to_le_bytes() != checksum
+    {
+        return None;
+    }
+    data[1..].try_into().ok()
+}
+
+/// CRC-16/XMODEM, the strkey checksum
+fn crc16_xmodem(data: &[u8]) -> u16 {
+    let mut crc = 0u16;
+    for byte in data {
+        crc ^= (*byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+/// NEAR account id rules: 2-64 characters, lowercase alphanumeric parts
+/// separated by single `.`, `-` or `_`
+fn is_near_account_id(id: &str) -> bool {
+    let mut last_separator = true;
+    for c in id.bytes() {
+        match c {
+            b'a'..=b'z' | b'0'..=b'9' => last_separator = false,
+            b'.' | b'-' | b'_' if !last_separator => last_separator = true,
+            _ => return false,
+        }
+    }
+    (2..=64).contains(&id.len()) && !last_separator
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ACCOUNT: &str = "GBRPYHIL2CI3FNQ4BXLFMNDLFJUNPU2HY3ZMFSHONUCEOASW7QC7OX2H";
+    const CONTRACT: &str = "CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE";
+
+    #[test]
+    fn test_stellar_addresses() {
+        assert!(parse_address(ChainId::Stellar, ACCOUNT).is_ok());
+        assert!(parse_address(ChainId::Stellar, CONTRACT).is_ok());
+
+        // One character off fails the checksum
+        let typo = ACCOUNT.replacen('Y', "Z", 1);
+        assert_eq!(
+            parse_address(ChainId::Stellar, &typo),
+            Err(AddressError::InvalidAddress(ChainId::Stellar))
+        );
+        assert!(parse_address(ChainId::Stellar, "GABC").is_err());
+        assert!(parse_address(ChainId::Stellar, &ACCOUNT.to_lowercase()).is_err());
+    }
+
+    #[test]
+    fn test_near_addresses_and_chain_ids() {
+        assert_eq!(
+            parse_address(ChainId::Near, "alice.near"),
+            Ok(contract_address("alice.near"))
+        );
+        assert!(parse_address(ChainId::Near, "Alice.near").is_err());
+        assert!(parse_address(ChainId::Near, "alice..near").is_err());
+
+        assert_eq!(ChainId::try_from(1), Ok(ChainId::Stellar));
+        assert_eq!(ChainId::try_from(9), Err(AddressError::UnknownChain(9)));
+    }
+}
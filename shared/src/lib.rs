@@ -2,6 +2,10 @@ use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use serde_with::serde_as;
 
+mod address;
+
+pub use address::{parse_address, AddressError};
+
 /// Chain identifiers for cross-chain messaging
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[repr(u8)]
@@ -62,6 +66,7 @@ pub struct Signature {
 
 impl VAA {
     /// Create a new VAA (before signing)
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         origin_chain: ChainId,
         origin_contract: [u8; 32],
@@ -127,32 +132,179 @@ impl VAA {
     /// Verify signatures against a set of authorized guardian public keys
     /// Returns true if enough valid signatures (meets quorum)
     pub fn verify_signatures(&self, authorized_guardians: &[[u8; 32]], quorum: usize) -> bool {
-        use ed25519_dalek::{Signature as Ed25519Sig, Verifier, VerifyingKey};
+        verify_quorum(&self.digest(), &self.signatures, authorized_guardians, quorum)
+    }
+}
 
-        if self.signatures.len() < quorum {
-            return false;
-        }
+/// Governance actions the guardian set can authorize on a bridge contract
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GovernanceAction {
+    /// Set the pause switches (inbound and outbound transfers)
+    SetPause { inbound: bool, outbound: bool },
 
-        let digest = self.digest();
-        let mut valid_count = 0;
+    /// Cancel a transfer queued by the governor (VAA nonce)
+    CancelQueuedTransfer { nonce: u64 },
 
-        for sig in &self.signatures {
-            // Check if this guardian is authorized
-            if !authorized_guardians.contains(&sig.guardian_pubkey) {
-                continue;
-            }
+    /// Mark an undelivered transfer as cancelled so it can be refunded
+    /// on the origin chain and never minted (VAA nonce)
+    CancelTransfer { nonce: u64 },
 
-            // Verify the signature
-            if let Ok(pubkey) = VerifyingKey::from_bytes(&sig.guardian_pubkey) {
-                let signature = Ed25519Sig::from_bytes(&sig.signature);
-                if pubkey.verify(&digest, &signature).is_ok() {
-                    valid_count += 1;
-                }
+    /// Cancel a timelocked parameter change before it executes (timelock id)
+    CancelTimelock { id: u64 },
+
+    /// Allow anyone to deploy contract code with this sha256 hash
+    Upgrade { code_hash: [u8; 32] },
+}
+
+impl GovernanceAction {
+    /// Canonical byte encoding of the action (tag + fields)
+    fn encode(&self) -> Vec<u8> {
+        match self {
+            GovernanceAction::SetPause { inbound, outbound } => {
+                vec![1, *inbound as u8, *outbound as u8]
+            }
+            GovernanceAction::CancelQueuedTransfer { nonce } => {
+                let mut bytes = vec![2];
+                bytes.extend_from_slice(&nonce.to_be_bytes());
+                bytes
+            }
+            GovernanceAction::CancelTransfer { nonce } => {
+                let mut bytes = vec![3];
+                bytes.extend_from_slice(&nonce.to_be_bytes());
+                bytes
             }
+            GovernanceAction::CancelTimelock { id } => {
+                let mut bytes = vec![4];
+                bytes.extend_from_slice(&id.to_be_bytes());
+                bytes
+            }
+            GovernanceAction::Upgrade { code_hash } => {
+                let mut bytes = vec![5];
+                bytes.extend_from_slice(code_hash);
+                bytes
+            }
+        }
+    }
+}
+
+/// Governance VAA - guardian-signed instruction for a bridge contract
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GovernanceVAA {
+    /// Version of the governance VAA format
+    pub version: u8,
+
+    /// Chain of the contract this instruction targets
+    pub target_chain: ChainId,
+
+    /// Address of the targeted contract (see `contract_address`)
+    pub target_contract: [u8; 32],
+
+    /// Strictly increasing sequence (replay protection)
+    pub sequence: u64,
+
+    /// Action to execute
+    pub action: GovernanceAction,
+
+    /// Guardian signatures (pubkey + signature pairs)
+    pub signatures: Vec<Signature>,
+}
+
+impl GovernanceVAA {
+    /// Create a new governance VAA (before signing)
+    pub fn new(
+        target_chain: ChainId,
+        target_contract: [u8; 32],
+        sequence: u64,
+        action: GovernanceAction,
+    ) -> Self {
+        Self {
+            version: 1,
+            target_chain,
+            target_contract,
+            sequence,
+            action,
+            signatures: Vec::new(),
+        }
+    }
+
+    /// Compute the hash that guardians will sign (message digest)
+    pub fn digest(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+
+        hasher.update(b"governance");
+        hasher.update([self.version]);
+        hasher.update([self.target_chain as u8]);
+        hasher.update(self.target_contract);
+        hasher.update(self.sequence.to_be_bytes());
+        hasher.update(self.action.encode());
+
+        hasher.finalize().into()
+    }
+
+    /// Add a guardian signature
+    pub fn add_signature(&mut self, guardian_pubkey: [u8; 32], signature: [u8; 64]) {
+        self.signatures.push(Signature {
+            guardian_pubkey,
+            signature,
+        });
+    }
+
+    /// Serialize to bytes
+    pub fn to_bytes(&self) -> Result<Vec<u8>, serde_json::Error> {
+        serde_json::to_vec(self)
+    }
+
+    /// Deserialize from bytes
+    pub fn from_bytes(data: &[u8]) -> Result<Self, serde_json::Error> {
+        serde_json::from_slice(data)
+    }
+
+    /// Verify signatures against a set of authorized guardian public keys
+    /// Returns true if enough valid signatures (meets quorum)
+    pub fn verify_signatures(&self, authorized_guardians: &[[u8; 32]], quorum: usize) -> bool {
+        verify_quorum(&self.digest(), &self.signatures, authorized_guardians, quorum)
+    }
+}
+
+/// 32-byte address of a contract identified by a string (e.g. a NEAR account id)
+pub fn contract_address(contract_id: &str) -> [u8; 32] {
+    Sha256::digest(contract_id.as_bytes()).into()
+}
+
+/// Count valid signatures over `digest` from distinct authorized guardians
+/// Returns true if the count meets quorum
+fn verify_quorum(
+    digest: &[u8; 32],
+    signatures: &[Signature],
+    authorized_guardians: &[[u8; 32]],
+    quorum: usize,
+) -> bool {
+    use ed25519_dalek::{Signature as Ed25519Sig, Verifier, VerifyingKey};
+
+    if signatures.len() < quorum {
+        return false;
+    }
+
+    let mut signers: Vec<[u8; 32]> = Vec::new();
+
+    for sig in signatures {
+        // Check if this guardian is authorized and not counted yet
+        if !authorized_guardians.contains(&sig.guardian_pubkey)
+            || signers.contains(&sig.guardian_pubkey)
+        {
+            continue;
         }
 
-        valid_count >= quorum
+        // Verify the signature
+        if let Ok(pubkey) = VerifyingKey::from_bytes(&sig.guardian_pubkey) {
+            let signature = Ed25519Sig::from_bytes(&sig.signature);
+            if pubkey.verify(digest, &signature).is_ok() {
+                signers.push(sig.guardian_pubkey);
+            }
+        }
     }
+
+    signers.len() >= quorum
 }
 
 #[cfg(test)]
@@ -201,4 +353,29 @@ mod tests {
         assert_eq!(vaa.nonce, decoded.nonce);
         assert_eq!(vaa.amount, decoded.amount);
     }
+
+    #[test]
+    fn test_duplicate_signatures_do_not_reach_quorum() {
+        use ed25519_dalek::{Signer, SigningKey};
+
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let pubkey = key.verifying_key().to_bytes();
+
+        let mut vaa = GovernanceVAA::new(
+            ChainId::Near,
+            contract_address("bridge.testnet"),
+            1,
+            GovernanceAction::SetPause {
+                inbound: true,
+                outbound: false,
+            },
+        );
+        let signature = key.sign(&vaa.digest()).to_bytes();
+        vaa.add_signature(pubkey, signature);
+
+        assert!(vaa.verify_signatures(&[pubkey], 1));
+
+        vaa.add_signature(pubkey, signature);
+        assert!(!vaa.verify_signatures(&[pubkey, [9u8; 32]], 2));
+    }
 }